@@ -1,2 +1,22 @@
+pub mod azure_devops;
+pub mod bitbucket;
+pub mod chatwork;
+pub mod discord;
+pub mod email;
+pub mod error;
+pub mod gitea;
 pub mod github;
-pub mod slack;
\ No newline at end of file
+pub mod github_app;
+pub mod gitlab;
+pub mod google_chat;
+pub mod html;
+pub mod line;
+pub mod markdown;
+pub mod matrix;
+pub mod mattermost;
+pub mod notifier;
+pub mod secret;
+pub mod slack;
+pub mod source;
+pub mod teams;
+pub mod webhook;
\ No newline at end of file
@@ -0,0 +1,292 @@
+use serde::Deserialize;
+use reqwest::Client;
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::adapter::{error, source::ChangeRequestSource};
+use crate::domain::model::{
+    azure_devops::{self, AzureList, AzureProject, AzurePullRequest},
+    change_request::{ChangeRequest, Forge},
+};
+
+const API_VERSION: &str = "7.1";
+const TOP: usize = 100;
+
+pub struct AzureDevopsApi {
+    client: Client,
+    token: String,
+    organization_uri: String,
+    projects: Vec<String>,
+}
+
+impl AzureDevopsApi {
+    /// `uri` is the organization (`https://dev.azure.com/contoso`) or, on
+    /// Azure DevOps Server, the collection URL; `token` is a PAT with
+    /// Code (Read) scope.
+    pub fn new(uri: &str, token: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            token: token.to_string(),
+            organization_uri: uri.trim_end_matches('/').to_string(),
+            projects: Vec::new(),
+        }
+    }
+
+    /// Project names; without any, every project the token can see.
+    pub fn projects(mut self, projects: Vec<String>) -> Self {
+        self.projects = projects;
+        self
+    }
+
+    /// Pages with `$top`/`$skip` until a short page comes back.
+    pub async fn fetch<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<Vec<T>> {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        let mut items = Vec::new();
+
+        for page in 0.. {
+            let url = format!(
+                "{}/{}{}$top={}&$skip={}&api-version={}",
+                self.organization_uri, path, separator, TOP, page * TOP, API_VERSION
+            );
+            let response = self.client
+                .get(&url)
+                // PATs go in as the password with an empty username.
+                .basic_auth("", Some(&self.token))
+                .send()
+                .await;
+
+            let batch = error::decode::<AzureList<T>>(&url, response).await?.value;
+            let last = batch.len() < TOP;
+
+            items.extend(batch);
+
+            if last {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    async fn project_names(&self) -> Result<Vec<String>> {
+        if !self.projects.is_empty() {
+            return Ok(self.projects.clone());
+        }
+
+        Ok(self.fetch::<AzureProject>("_apis/projects")
+            .await?
+            .into_iter()
+            .map(|project| project.name)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ChangeRequestSource for AzureDevopsApi {
+    fn forge(&self) -> Forge {
+        Forge::AzureDevops
+    }
+
+    async fn change_requests(&self, repos: &[String]) -> Result<Vec<ChangeRequest>> {
+        let mut change_requests = Vec::new();
+
+        for project in self.project_names().await? {
+            let pulls = self
+                .fetch::<AzurePullRequest>(&format!(
+                    "{}/_apis/git/pullrequests?searchCriteria.status=active",
+                    azure_devops::path_segment(&project)
+                ))
+                .await?;
+
+            for pull in pulls {
+                if pull.repository().matches(repos) {
+                    change_requests.push(pull.change_request(&self.organization_uri)?);
+                }
+            }
+        }
+
+        Ok(change_requests)
+    }
+}
+
+#[cfg(test)]
+mod azure_devops_api_test {
+    use rstest::{rstest, fixture};
+
+    use super::*;
+    use crate::domain::model::change_request::{ReviewStatus, ReviewVerdict};
+
+    // base64(":azure-token")
+    const AUTHORIZATION: &str = "Basic OmF6dXJlLXRva2Vu";
+
+    fn reviewer(unique_name: &str, vote: i32) -> serde_json::Value {
+        serde_json::json!({ "displayName": unique_name, "uniqueName": unique_name, "vote": vote })
+    }
+
+    #[fixture]
+    fn pull_request() -> serde_json::Value {
+        serde_json::json!({
+            "pullRequestId": 42,
+            "title": "Add Azure DevOps source",
+            "status": "active",
+            "isDraft": true,
+            "createdBy": { "displayName": "reo", "uniqueName": "reo0306@example.com", "imageUrl": "https://example.com/reo.png" },
+            "creationDate": "2024-07-16T20:09:31.1234567Z",
+            "repository": {
+                "name": "gospo",
+                "url": "https://dev.azure.com/contoso/_apis/git/repositories/1",
+                "project": { "name": "Tools" }
+            },
+            "reviewers": [
+                reviewer("approve@example.com", 10),
+                reviewer("suggest@example.com", 5),
+                reviewer("none@example.com", 0),
+                reviewer("wait@example.com", -5),
+                reviewer("reject@example.com", -10),
+                { "displayName": "[Tools]\\Tools Team", "uniqueName": "vstfs:///Classification/TeamProject/1\\Tools Team", "vote": 0, "isContainer": true },
+            ]
+        })
+    }
+
+    #[rstest]
+    #[case(10, ReviewVerdict::Approved, ReviewStatus::Approved)]
+    #[case(5, ReviewVerdict::Approved, ReviewStatus::Approved)]
+    #[case(0, ReviewVerdict::Pending, ReviewStatus::AwaitingReview)]
+    #[case(-5, ReviewVerdict::ChangesRequested, ReviewStatus::ChangesRequested)]
+    #[case(-10, ReviewVerdict::ChangesRequested, ReviewStatus::ChangesRequested)]
+    fn test_verdict_by_vote(
+        mut pull_request: serde_json::Value,
+        #[case] vote: i32,
+        #[case] verdict: ReviewVerdict,
+        #[case] status: ReviewStatus,
+    ) {
+        pull_request["reviewers"] = serde_json::json!([reviewer("reo0306@example.com", vote)]);
+
+        let pull = serde_json::from_value::<AzurePullRequest>(pull_request)
+            .unwrap()
+            .change_request("https://dev.azure.com/contoso")
+            .unwrap();
+
+        assert_eq!(verdict, pull.reviewers[0].verdict);
+        assert_eq!(status, pull.status());
+    }
+
+    #[rstest]
+    fn test_html_url_encodes_names(mut pull_request: serde_json::Value) {
+        pull_request["repository"]["name"] = serde_json::json!("web app");
+        pull_request["repository"]["project"]["name"] = serde_json::json!("R&D #2");
+
+        let pull = serde_json::from_value::<AzurePullRequest>(pull_request)
+            .unwrap()
+            .change_request("https://dev.azure.com/contoso")
+            .unwrap();
+
+        assert_eq!("https://dev.azure.com/contoso/R%26D%20%232/_git/web%20app/pullrequest/42", pull.html_url);
+    }
+
+    async fn mock_server(server: &mut mockito::ServerGuard, path: &str, query: &str, body: String) -> mockito::Mock {
+        server
+            .mock("GET", path)
+            .match_query(mockito::Matcher::Exact(query.to_string()))
+            .match_header("authorization", AUTHORIZATION)
+            .with_status(200)
+            .with_body(body)
+            .create_async()
+            .await
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn it_change_requests(pull_request: serde_json::Value) {
+        let mut server = mockito::Server::new_async().await;
+        let azure_devops_api = AzureDevopsApi::new(&server.url(), "azure-token");
+
+        let mocks = vec![
+            mock_server(
+                &mut server,
+                "/_apis/projects",
+                "$top=100&$skip=0&api-version=7.1",
+                serde_json::json!({ "count": 1, "value": [{ "name": "Tools" }] }).to_string(),
+            ).await,
+            mock_server(
+                &mut server,
+                "/Tools/_apis/git/pullrequests",
+                "searchCriteria.status=active&$top=100&$skip=0&api-version=7.1",
+                serde_json::json!({ "count": 1, "value": [pull_request] }).to_string(),
+            ).await,
+        ];
+
+        let change_requests = azure_devops_api.change_requests(&[]).await.unwrap();
+
+        assert_eq!(1, change_requests.len());
+
+        let pull = &change_requests[0];
+        assert_eq!(Forge::AzureDevops, pull.repo.forge);
+        assert_eq!("Tools/gospo", pull.repo.full_name);
+        assert_eq!(42, pull.number);
+        assert!(pull.draft);
+        assert_eq!(format!("{}/Tools/_git/gospo/pullrequest/42", server.url()), pull.html_url);
+        assert_eq!("reo0306@example.com", pull.user.login);
+        assert_eq!("mailto:reo0306@example.com", pull.user.html_url);
+        assert_eq!("2024-07-16T20:09:31", pull.created_at.format("%Y-%m-%dT%H:%M:%S").to_string());
+        assert_eq!(
+            vec![
+                ("approve@example.com", ReviewVerdict::Approved),
+                ("suggest@example.com", ReviewVerdict::Approved),
+                ("none@example.com", ReviewVerdict::Pending),
+                ("wait@example.com", ReviewVerdict::ChangesRequested),
+                ("reject@example.com", ReviewVerdict::ChangesRequested),
+            ],
+            pull.reviewers
+                .iter()
+                .map(|reviewer| (reviewer.user.login.as_str(), reviewer.verdict))
+                .collect::<Vec<(&str, ReviewVerdict)>>()
+        );
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn it_uses_configured_projects(pull_request: serde_json::Value) {
+        let mut server = mockito::Server::new_async().await;
+        let azure_devops_api = AzureDevopsApi::new(&format!("{}/", server.url()), "azure-token")
+            .projects(vec!["Tools".to_string(), "Web Site".to_string()]);
+
+        let tools = mock_server(
+            &mut server,
+            "/Tools/_apis/git/pullrequests",
+            "searchCriteria.status=active&$top=100&$skip=0&api-version=7.1",
+            serde_json::json!({ "count": 1, "value": [pull_request] }).to_string(),
+        ).await;
+        let web_site = mock_server(
+            &mut server,
+            "/Web%20Site/_apis/git/pullrequests",
+            "searchCriteria.status=active&$top=100&$skip=0&api-version=7.1",
+            serde_json::json!({ "count": 0, "value": [] }).to_string(),
+        ).await;
+
+        assert_eq!(1, azure_devops_api.change_requests(&["gospo".to_string()]).await.unwrap().len());
+        assert!(azure_devops_api.change_requests(&["other".to_string()]).await.unwrap().is_empty());
+
+        tools.expect_at_least(1).assert_async().await;
+        web_site.expect_at_least(1).assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn it_fetches_until_short_page() {
+        let mut server = mockito::Server::new_async().await;
+        let azure_devops_api = AzureDevopsApi::new(&server.url(), "azure-token");
+
+        let full = (0..TOP).map(|i| serde_json::json!({ "name": format!("p{}", i) })).collect::<Vec<_>>();
+        let first = mock_server(&mut server, "/_apis/projects", "$top=100&$skip=0&api-version=7.1", serde_json::json!({ "value": full }).to_string()).await;
+        let second = mock_server(&mut server, "/_apis/projects", "$top=100&$skip=100&api-version=7.1", serde_json::json!({ "value": [] }).to_string()).await;
+
+        assert_eq!(TOP, azure_devops_api.fetch::<AzureProject>("_apis/projects").await.unwrap().len());
+
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+}
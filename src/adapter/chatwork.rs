@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::adapter::{
+    error::Error,
+    notifier::{self, Notifier, Payload, Rendered},
+};
+use crate::domain::model::{
+    change_request::{ChangeRequest, ReviewVerdict, TextLine},
+    digest::Digest,
+    locale::{Label, Locale},
+};
+
+pub const CHATWORK_API_URI: &str = "https://api.chatwork.com/v2";
+const MAX_PULLS: usize = 30;
+
+/// The form `send` posts to the room.
+#[derive(Serialize, Debug)]
+pub struct ChatworkMessage {
+    pub body: String,
+}
+
+pub struct ChatworkApi;
+
+impl ChatworkApi {
+    /// `users` maps GitHub logins to Chatwork account ids; mapped reviewers
+    /// who have not approved yet get a `[To:id]`.
+    pub fn construct_digest(
+        pulls: &[ChangeRequest],
+        locale: Locale,
+        users: &HashMap<String, String>,
+        now: NaiveDateTime,
+    ) -> String {
+        let text_lines = pulls
+            .iter()
+            .map(|pull| pull.text_line().locale(locale))
+            .collect::<Vec<TextLine>>();
+
+        let mut mentions = Vec::new();
+        for text_line in &text_lines {
+            for (reviewer, state) in text_line.reviewer_states() {
+                if let Some(account_id) = users.get(&reviewer.login).filter(|_| state != ReviewVerdict::Approved) {
+                    let mention = format!("[To:{}]{}", account_id, Self::escape(&reviewer.login));
+
+                    if !mentions.contains(&mention) {
+                        mentions.push(mention);
+                    }
+                }
+            }
+        }
+
+        let mut items = text_lines
+            .iter()
+            .take(MAX_PULLS)
+            .map(|text_line| Self::pull_request(text_line, now))
+            .collect::<Vec<String>>();
+
+        if text_lines.len() > MAX_PULLS {
+            items.push(locale.more(text_lines.len() - MAX_PULLS));
+        }
+
+        let info = format!(
+            "[info][title]{} ({})[/title]{}[/info]",
+            locale.label(Label::OpenPullRequest),
+            locale.open_pull_requests(text_lines.len()),
+            items.join("[hr]")
+        );
+
+        if mentions.is_empty() {
+            info
+        } else {
+            format!("{}\n{}", mentions.join(" "), info)
+        }
+    }
+
+    fn pull_request(text_line: &TextLine, now: NaiveDateTime) -> String {
+        let locale = text_line.locale;
+        let pull = text_line.pull;
+
+        let reviewers = text_line
+            .reviewer_states()
+            .iter()
+            .map(|(reviewer, state)| format!("{} {}", Self::review_emoji(*state), Self::escape(&reviewer.login)))
+            .collect::<Vec<String>>();
+
+        format!(
+            "{} - {}#{}\n{}\n{}: {} / {}: {}\n{} · {}\n",
+            Self::escape(&pull.short_title()),
+            Self::escape(&text_line.repo.full_name),
+            pull.number,
+            pull.html_url,
+            locale.label(Label::Status),
+            Self::escape(&pull.state),
+            locale.label(Label::Reviewers),
+            if reviewers.is_empty() { locale.label(Label::NoReviewers).to_string() } else { reviewers.join(", ") },
+            locale.created_by(&Self::escape(&pull.user.login), &text_line.created_at()),
+            locale.relative_age(&text_line.age(now))
+        )
+    }
+
+    /// Chatwork has no escaping, so break `[toall]`, `[To:id]` or `[/info]`
+    /// in PR data with a zero-width space.
+    fn escape(text: &str) -> String {
+        text.replace('[', "[\u{200B}")
+    }
+
+    fn review_emoji(state: ReviewVerdict) -> &'static str {
+        match state {
+            ReviewVerdict::Approved => "(F)",
+            ReviewVerdict::ChangesRequested => "(devil)",
+            ReviewVerdict::Commented => "(*)",
+            ReviewVerdict::Pending => "(wait)",
+        }
+    }
+
+    pub async fn post(api_uri: &str, room_id: &str, api_token: &str, body: &str) -> Result<(), Error> {
+        let url = format!("{}/rooms/{}/messages", api_uri.trim_end_matches('/'), room_id);
+
+        let request = reqwest::Client::new()
+            .post(&url)
+            .header("X-ChatWorkToken", api_token)
+            .form(&[("body", body)]);
+
+        notifier::send(&url, request).await?;
+
+        Ok(())
+    }
+}
+
+pub struct ChatworkNotifier {
+    name: String,
+    room_id: String,
+    api_uri: String,
+    api_token: String,
+    locale: Locale,
+    users: HashMap<String, String>,
+}
+
+impl ChatworkNotifier {
+    pub fn new(name: &str, room_id: &str, api_token: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            room_id: room_id.to_string(),
+            api_uri: CHATWORK_API_URI.to_string(),
+            api_token: api_token.to_string(),
+            locale: Locale::default(),
+            users: HashMap::new(),
+        }
+    }
+
+    pub fn api_uri(mut self, api_uri: &str) -> Self {
+        self.api_uri = api_uri.to_string();
+        self
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn users(mut self, users: HashMap<String, String>) -> Self {
+        self.users = users;
+        self
+    }
+}
+
+#[async_trait]
+impl Notifier for ChatworkNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, digest: &Digest) -> anyhow::Result<Rendered> {
+        let body = ChatworkApi::construct_digest(&digest.pulls, self.locale, &self.users, digest.generated_at);
+
+        Ok(Rendered {
+            text: body.clone(),
+            payload: Payload::Chatwork(ChatworkMessage { body }),
+        })
+    }
+
+    async fn send(&self, rendered: &Rendered) -> anyhow::Result<()> {
+        let Payload::Chatwork(message) = &rendered.payload else {
+            return Err(notifier::unexpected_payload(&self.name));
+        };
+
+        Ok(ChatworkApi::post(&self.api_uri, &self.room_id, &self.api_token, &message.body).await?)
+    }
+}
+
+#[cfg(test)]
+mod chatwork_test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::domain::model::change_request::fixture::{now, open_pull_request, open_pull_request_titled};
+
+    #[test]
+    fn test_construct_digest() {
+        let users = HashMap::from([
+            ("test".to_string(), "1001".to_string()),
+            ("test2".to_string(), "1002".to_string()),
+        ]);
+
+        let body = ChatworkApi::construct_digest(&[open_pull_request(1)], Locale::Ja, &users, now());
+
+        assert_eq!(
+            "[To:1002]test2\n\
+            [info][title]オープン中のプルリクエスト (オープン中のプルリクエスト 1 件)[/title]\
+            Test - reo0306/gospo#1\nhttps://github.com/reo0306/gospo/pull/1\n\
+            ステータス: open / レビュアー: (F) test, (wait) test2\n\
+            reo0306 が 2024年07月16日 20:09 に作成 · 2日前\n[/info]",
+            body
+        );
+    }
+
+    #[test]
+    fn test_construct_digest_escapes_tags() {
+        let body = ChatworkApi::construct_digest(&[open_pull_request_titled("[toall] [/info]")], Locale::En, &HashMap::new(), now());
+
+        assert!(body.contains("[\u{200B}toall] [\u{200B}/info]"));
+        assert!(!body.contains("[toall]"));
+    }
+
+    #[rstest]
+    #[case(200, true)]
+    #[case(401, false)]
+    #[tokio::test]
+    async fn test_chatwork_notifier_notify(#[case] status: usize, #[case] ok: bool) {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/rooms/42/messages")
+            .match_header("x-chatworktoken", "api-token")
+            .match_header("content-type", "application/x-www-form-urlencoded")
+            .match_body(mockito::Matcher::Regex("^body=%5Binfo%5D%5Btitle%5D".to_string()))
+            .with_status(status)
+            .with_body(r#"{"message_id": "1"}"#)
+            .create_async()
+            .await;
+
+        let notifier = ChatworkNotifier::new("chatwork", "42", "api-token").api_uri(&server.url());
+        let report = notifier.notify(&Digest::new(vec![open_pull_request(1)], now())).await;
+
+        assert_eq!(ok, report.result.is_ok());
+
+        mock.assert_async().await;
+    }
+}
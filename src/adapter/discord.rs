@@ -0,0 +1,321 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+use crate::adapter::{
+    error::Error,
+    markdown,
+    notifier::{self, Notifier, Payload, Rendered},
+};
+use crate::domain::text;
+use crate::domain::model::{
+    change_request::{ChangeRequest, ReviewStatus, TextLine},
+    digest::Digest,
+    discord::{Discord, Embed, EmbedField},
+    locale::{Label, Locale},
+};
+
+// https://discord.com/developers/docs/resources/message#embed-object-embed-limits
+const MAX_EMBEDS: usize = 10;
+const MAX_EMBED_CHARS: usize = 6000;
+const MAX_DESCRIPTION_CHARS: usize = 4096;
+const MAX_FIELD_VALUE_CHARS: usize = 1024;
+// Names the endpoint in errors; the webhook URL is itself the secret.
+const WEBHOOK: &str = "Discord webhook";
+
+const COLOR_APPROVED: u32 = 0x57F287;
+const COLOR_CHANGES_REQUESTED: u32 = 0xED4245;
+const COLOR_PENDING: u32 = 0xFEE75C;
+
+pub struct DiscordApi;
+
+impl DiscordApi {
+    /// Builds one webhook message per batch of embeds; only the first carries the header.
+    pub fn construct_digest(pulls: &[ChangeRequest], locale: Locale, now: NaiveDateTime) -> Vec<Discord> {
+        let embeds = pulls
+            .iter()
+            .map(|pull| Self::pull_request_embed(&pull.text_line().locale(locale), now))
+            .collect::<Vec<Embed>>();
+
+        let header = format!("**{}**\n{}", locale.label(Label::OpenPullRequest), locale.open_pull_requests(pulls.len()));
+
+        let mut messages = Self::split(embeds)
+            .into_iter()
+            .map(|embeds| Discord::new(None, embeds))
+            .collect::<Vec<Discord>>();
+
+        match messages.first_mut() {
+            Some(first) => first.content = Some(header),
+            None => messages.push(Discord::new(Some(header), Vec::new())),
+        }
+
+        messages
+    }
+
+    /// Groups embeds so no message exceeds the embed count or total character limit.
+    pub fn split(embeds: Vec<Embed>) -> Vec<Vec<Embed>> {
+        let mut messages: Vec<Vec<Embed>> = Vec::new();
+        let mut chars = 0;
+
+        for embed in embeds {
+            let embed_chars = embed.chars();
+
+            match messages.last_mut() {
+                Some(last) if last.len() < MAX_EMBEDS && chars + embed_chars <= MAX_EMBED_CHARS => {
+                    chars += embed_chars;
+                    last.push(embed);
+                },
+                _ => {
+                    chars = embed_chars;
+                    messages.push(vec![embed]);
+                }
+            }
+        }
+
+        messages
+    }
+
+    fn pull_request_embed(text_line: &TextLine, now: NaiveDateTime) -> Embed {
+        let locale = text_line.locale;
+        let pull = text_line.pull;
+        let states = text_line.reviewer_states();
+
+        let reviewers = states
+            .iter()
+            .map(|(reviewer, state)| format!("{} {}", state.emoji(), markdown::escape(&reviewer.login)))
+            .collect::<Vec<String>>();
+
+        let description = format!(
+            "{}#{}\n{} · {}",
+            markdown::escape(&text_line.repo.full_name),
+            pull.number,
+            locale.created_by(&markdown::link(&pull.user.html_url, &pull.user.login), &text_line.created_at()),
+            locale.relative_age(&text_line.age(now))
+        );
+
+        Embed {
+            title: pull.short_title(),
+            url: pull.html_url.to_string(),
+            description: text::truncate(&description, MAX_DESCRIPTION_CHARS),
+            color: Self::color(pull.status()),
+            fields: vec![
+                EmbedField::new(locale.label(Label::Status), &markdown::escape(&pull.state)),
+                EmbedField::new(
+                    locale.label(Label::Reviewers),
+                    &if reviewers.is_empty() {
+                        locale.label(Label::NoReviewers).to_string()
+                    } else {
+                        text::truncate(&reviewers.join("\n"), MAX_FIELD_VALUE_CHARS)
+                    }
+                ),
+            ],
+            footer: None,
+        }
+    }
+
+    fn color(status: ReviewStatus) -> u32 {
+        match status {
+            ReviewStatus::ChangesRequested => COLOR_CHANGES_REQUESTED,
+            ReviewStatus::Approved => COLOR_APPROVED,
+            ReviewStatus::Unassigned | ReviewStatus::AwaitingReview => COLOR_PENDING,
+        }
+    }
+
+    /// POSTs one message, waiting out `Retry-After` when Discord answers 429.
+    pub async fn post(webhook_uri: &str, payload: &Discord) -> Result<(), Error> {
+        notifier::send_with_retry(WEBHOOK, reqwest::Client::new().post(webhook_uri).json(payload)).await?;
+
+        Ok(())
+    }
+}
+
+pub struct DiscordNotifier {
+    name: String,
+    webhook_uri: String,
+    locale: Locale,
+}
+
+impl DiscordNotifier {
+    pub fn new(name: &str, webhook_uri: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            webhook_uri: webhook_uri.to_string(),
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The payload is an array of webhook messages, posted in order.
+    fn render(&self, digest: &Digest) -> anyhow::Result<Rendered> {
+        let messages = DiscordApi::construct_digest(&digest.pulls, self.locale, digest.generated_at);
+
+        Ok(Rendered {
+            text: digest.text(self.locale),
+            payload: Payload::Discord(messages),
+        })
+    }
+
+    async fn send(&self, rendered: &Rendered) -> anyhow::Result<()> {
+        let Payload::Discord(messages) = &rendered.payload else {
+            return Err(notifier::unexpected_payload(&self.name));
+        };
+
+        for message in messages {
+            DiscordApi::post(&self.webhook_uri, message).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod discord_test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::domain::model::change_request;
+    use crate::domain::model::change_request::{
+        ReviewVerdict,
+        fixture::{now, open_pull_request, reviewer},
+    };
+
+    #[test]
+    fn test_construct_digest() {
+        let messages = DiscordApi::construct_digest(&[open_pull_request(1)], Locale::En, now());
+
+        assert_eq!(
+            serde_json::json!([{
+                "content": "**Open Pull Request**\n1 open pull request",
+                "allowed_mentions": { "parse": [] },
+                "embeds": [{
+                    "title": "Test",
+                    "url": "https://github.com/reo0306/gospo/pull/1",
+                    "description": "reo0306/gospo#1\nCreated by [reo0306](https://github.com/reo0306) on 2024-07-16 20:09:31 · 2 days ago",
+                    "color": COLOR_PENDING,
+                    "fields": [
+                        { "name": "Status", "value": "open", "inline": true },
+                        { "name": "Reviewers", "value": "✅ test\n⏳ test2", "inline": true },
+                    ]
+                }]
+            }]),
+            serde_json::json!(messages)
+        );
+    }
+
+    #[test]
+    fn test_construct_digest_empty() {
+        let messages = DiscordApi::construct_digest(&[], Locale::Ja, now());
+
+        assert_eq!(
+            vec![Discord::new(Some("**オープン中のプルリクエスト**\nオープン中のプルリクエストはありません".to_string()), Vec::new())],
+            messages
+        );
+    }
+
+    #[rstest]
+    #[case(vec![ReviewVerdict::Approved, ReviewVerdict::Approved], COLOR_APPROVED)]
+    #[case(vec![ReviewVerdict::Approved, ReviewVerdict::ChangesRequested], COLOR_CHANGES_REQUESTED)]
+    #[case(vec![ReviewVerdict::Commented, ReviewVerdict::Pending], COLOR_PENDING)]
+    #[case(Vec::new(), COLOR_PENDING)]
+    fn test_color_by_status(#[case] verdicts: Vec<ReviewVerdict>, #[case] expected: u32) {
+        let pull = ChangeRequest {
+            reviewers: ["test", "test2"].into_iter().zip(verdicts).map(|(login, verdict)| reviewer(login, verdict)).collect(),
+            ..open_pull_request(1)
+        };
+
+        let messages = DiscordApi::construct_digest(&[pull], Locale::En, now());
+
+        assert_eq!(expected, messages[0].embeds[0].color);
+    }
+
+    #[test]
+    fn test_split_by_embed_count() {
+        let pulls = (1..=25).map(open_pull_request).collect::<Vec<ChangeRequest>>();
+
+        let messages = DiscordApi::construct_digest(&pulls, Locale::En, now());
+
+        assert_eq!(vec![10, 10, 5], messages.iter().map(|message| message.embeds.len()).collect::<Vec<usize>>());
+        assert!(messages[0].content.is_some());
+        assert!(messages[1].content.is_none());
+    }
+
+    #[test]
+    fn test_split_by_characters() {
+        let embed = Embed {
+            description: "a".repeat(2500),
+            ..Default::default()
+        };
+
+        let messages = DiscordApi::split(vec![embed.clone(), embed.clone(), embed.clone(), embed]);
+
+        assert_eq!(vec![2, 2], messages.iter().map(|message| message.len()).collect::<Vec<usize>>());
+        assert!(messages.iter().all(|message| message.iter().map(Embed::chars).sum::<usize>() <= MAX_EMBED_CHARS));
+    }
+
+    #[test]
+    fn test_embed_truncates_long_title() {
+        let mut pull = open_pull_request(1);
+        pull.title = "x".repeat(300);
+
+        let messages = DiscordApi::construct_digest(&[pull], Locale::En, now());
+
+        assert_eq!(change_request::MAX_TITLE_CHARS, messages[0].embeds[0].title.chars().count());
+    }
+
+    #[tokio::test]
+    async fn test_discord_notifier_retries_after_rate_limit() {
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("POST", "/api/webhooks/1/token")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .with_body(r#"{"message": "You are being rate limited.", "retry_after": 0.01, "global": false}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let accepted = server
+            .mock("POST", "/api/webhooks/1/token")
+            .with_status(204)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let notifier = DiscordNotifier::new("discord", &format!("{}/api/webhooks/1/token", server.url()));
+        let report = notifier.notify(&Digest::new(vec![open_pull_request(1)], now())).await;
+
+        assert!(report.result.is_ok());
+
+        limited.assert_async().await;
+        accepted.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_discord_notifier_gives_up_after_retries() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/webhooks/1/token")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .with_body(r#"{"retry_after": 0.01}"#)
+            .expect(notifier::MAX_RETRIES + 1)
+            .create_async()
+            .await;
+
+        let notifier = DiscordNotifier::new("discord", &format!("{}/api/webhooks/1/token", server.url()));
+        let report = notifier.notify(&Digest::new(Vec::new(), now())).await;
+
+        assert!(report.result.is_err());
+
+        mock.assert_async().await;
+    }
+}
@@ -0,0 +1,364 @@
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use lettre::{
+    message::{Mailbox, MultiPart},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport,
+    AsyncTransport,
+    Message,
+    Tokio1Executor,
+};
+use serde::{Serialize, Deserialize};
+
+use crate::adapter::{
+    html,
+    notifier::{self, Notifier, Payload, Rendered},
+};
+use crate::domain::model::{
+    change_request::TextLine,
+    digest::Digest,
+    locale::{Label, Locale},
+};
+
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    /// Plain connection upgraded with STARTTLS, usually port 587.
+    #[default]
+    Starttls,
+    /// TLS from the first byte, usually port 465.
+    Implicit,
+    /// No encryption at all; only for local relays and test sinks.
+    None,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Email {
+    pub subject: String,
+    /// Sent as the plain-text part and shown as the rendered text.
+    #[serde(skip)]
+    pub text: String,
+    pub html: String,
+}
+
+/// An `Email` with who it goes from and to.
+#[derive(Serialize, Debug)]
+pub struct Envelope {
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(flatten)]
+    pub email: Email,
+}
+
+pub struct EmailApi;
+
+impl EmailApi {
+    /// The subject carries the digest's local date, so a run early in the
+    /// morning east of UTC is not labelled with the day before.
+    pub fn construct_digest(digest: &Digest, locale: Locale) -> Email {
+        let now = digest.generated_at;
+        let text_lines = digest.pulls
+            .iter()
+            .map(|pull| pull.text_line().locale(locale))
+            .collect::<Vec<TextLine>>();
+
+        let count = locale.open_pull_requests(text_lines.len());
+
+        let mut text = vec![locale.label(Label::OpenPullRequest).to_string(), String::new()];
+        let mut items = Vec::new();
+
+        for text_line in &text_lines {
+            text.push(Self::pull_request_text(text_line, now));
+            items.push(Self::pull_request_html(text_line, now));
+        }
+
+        text.push(count.to_string());
+
+        let html = format!(
+            "<!DOCTYPE html>\n<html>\n<body>\n<h2>{}</h2>\n<ul>\n{}</ul>\n<p>{}</p>\n</body>\n</html>\n",
+            html::escape(locale.label(Label::OpenPullRequest)),
+            items.concat(),
+            html::escape(&count)
+        );
+
+        Email {
+            subject: format!("{} ({})", count, digest.local_date()),
+            text: text.join("\n"),
+            html,
+        }
+    }
+
+    fn pull_request_text(text_line: &TextLine, now: NaiveDateTime) -> String {
+        let locale = text_line.locale;
+        let pull = text_line.pull;
+
+        format!(
+            "* {} ({}#{})\n  {}\n  {}: {} / {}: {}\n  {} · {}\n",
+            pull.short_title(),
+            text_line.repo.full_name,
+            pull.number,
+            pull.html_url,
+            locale.label(Label::Status),
+            pull.state,
+            locale.label(Label::Reviewers),
+            html::reviewers(text_line, |login| login.to_string()),
+            locale.created_by(&pull.user.login, &text_line.created_at()),
+            locale.relative_age(&text_line.age(now))
+        )
+    }
+
+    fn pull_request_html(text_line: &TextLine, now: NaiveDateTime) -> String {
+        let locale = text_line.locale;
+        let pull = text_line.pull;
+
+        format!(
+            "<li>\n<b>{}</b> <code>{}#{}</code><br>\n{}: {} / {}: {}<br>\n<small>{} · {}</small>\n</li>\n",
+            html::link(&pull.html_url, &pull.short_title()),
+            html::escape(&text_line.repo.full_name),
+            pull.number,
+            html::escape(locale.label(Label::Status)),
+            html::escape(&pull.state),
+            html::escape(locale.label(Label::Reviewers)),
+            html::reviewers(text_line, html::escape),
+            locale.created_by(&html::link(&pull.user.html_url, &pull.user.login), &html::escape(&text_line.created_at())),
+            html::escape(&locale.relative_age(&text_line.age(now)))
+        )
+    }
+}
+
+pub struct EmailNotifier {
+    name: String,
+    host: String,
+    port: Option<u16>,
+    tls: SmtpTls,
+    credentials: Option<Credentials>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    locale: Locale,
+}
+
+impl EmailNotifier {
+    pub fn new(name: &str, host: &str, from: Mailbox, to: Vec<Mailbox>) -> Self {
+        Self {
+            name: name.to_string(),
+            host: host.to_string(),
+            port: None,
+            tls: SmtpTls::default(),
+            credentials: None,
+            from,
+            to,
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    pub fn tls(mut self, tls: SmtpTls) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn credentials(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some(Credentials::new(username.to_string(), password.to_string()));
+        self
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    fn transport(&self) -> anyhow::Result<AsyncSmtpTransport<Tokio1Executor>> {
+        let mut builder = match self.tls {
+            SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host)?,
+            SmtpTls::Implicit => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)?,
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.host),
+        };
+
+        if let Some(port) = self.port {
+            builder = builder.port(port);
+        }
+
+        if let Some(credentials) = &self.credentials {
+            builder = builder.credentials(credentials.clone());
+        }
+
+        Ok(builder.build())
+    }
+
+    fn message(&self, email: &Email) -> anyhow::Result<Message> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(&email.subject);
+
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+
+        Ok(builder.multipart(MultiPart::alternative_plain_html(email.text.to_string(), email.html.to_string()))?)
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, digest: &Digest) -> anyhow::Result<Rendered> {
+        let email = EmailApi::construct_digest(digest, self.locale);
+
+        Ok(Rendered {
+            text: email.text.clone(),
+            payload: Payload::Email(Envelope {
+                from: self.from.to_string(),
+                to: self.to.iter().map(Mailbox::to_string).collect(),
+                email,
+            }),
+        })
+    }
+
+    async fn send(&self, rendered: &Rendered) -> anyhow::Result<()> {
+        let Payload::Email(envelope) = &rendered.payload else {
+            return Err(notifier::unexpected_payload(&self.name));
+        };
+
+        let message = self.message(&envelope.email)?;
+
+        self.transport()?
+            .send(message)
+            .await
+            .with_context(|| format!("failed to send email via {}", self.host))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod email_test {
+    use chrono_tz::Tz;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    use super::*;
+    use crate::domain::model::change_request::fixture::{now, open_pull_request, open_pull_request_titled};
+
+    /// Accepts a single SMTP session and returns the commands and message data it received.
+    async fn smtp_sink() -> (u16, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let mut received = String::new();
+            let mut in_data = false;
+
+            writer.write_all(b"220 localhost ESMTP sink\r\n").await.unwrap();
+
+            while let Some(line) = lines.next_line().await.unwrap() {
+                received.push_str(&line);
+                received.push('\n');
+
+                let reply: &[u8] = if in_data {
+                    if line != "." {
+                        continue;
+                    }
+                    in_data = false;
+                    b"250 queued\r\n"
+                } else if line.starts_with("EHLO") {
+                    b"250-localhost\r\n250 AUTH PLAIN LOGIN\r\n"
+                } else if line.starts_with("AUTH") {
+                    b"235 authenticated\r\n"
+                } else if line == "DATA" {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line == "QUIT" {
+                    writer.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+
+                writer.write_all(reply).await.unwrap();
+            }
+
+            received
+        });
+
+        (port, handle)
+    }
+
+    #[test]
+    fn test_construct_digest() {
+        let email = EmailApi::construct_digest(&Digest::new(vec![open_pull_request(1)], now()), Locale::En);
+
+        assert_eq!("1 open pull request (2024-07-19)", email.subject);
+        assert_eq!(
+            "Open Pull Request\n\n\
+            * Test (reo0306/gospo#1)\n  https://github.com/reo0306/gospo/pull/1\n  Status: open / Reviewers: ✅ test, ⏳ test2\n  Created by reo0306 on 2024-07-16 20:09:31 · 2 days ago\n\n\
+            1 open pull request",
+            email.text
+        );
+        assert!(email.html.contains(
+            "<li>\n<b><a href=\"https://github.com/reo0306/gospo/pull/1\">Test</a></b> <code>reo0306/gospo#1</code><br>\n\
+            Status: open / Reviewers: ✅ test, ⏳ test2<br>\n\
+            <small>Created by <a href=\"https://github.com/reo0306\">reo0306</a> on 2024-07-16 20:09:31 · 2 days ago</small>\n</li>\n"
+        ));
+    }
+
+    #[test]
+    fn test_construct_digest_escapes_html() {
+        let email = EmailApi::construct_digest(&Digest::new(vec![open_pull_request_titled("<script>alert(1)</script>")], now()), Locale::Ja);
+
+        assert!(email.html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!email.html.contains("<script>"));
+        assert!(email.text.contains("<script>alert(1)</script>"));
+    }
+
+    #[test]
+    fn test_construct_digest_subject_uses_local_date() {
+        // 08:00 on the 20th in Tokyo is still the 19th in UTC.
+        let generated_at = NaiveDateTime::parse_from_str("2024-07-19T23:00:00Z", "%Y-%m-%dT%H:%M:%SZ").unwrap();
+        let digest = Digest::new(Vec::new(), generated_at).timezone(Tz::Asia__Tokyo);
+
+        assert_eq!("No open pull requests (2024-07-20)", EmailApi::construct_digest(&digest, Locale::En).subject);
+    }
+
+    #[tokio::test]
+    async fn test_email_notifier_sends_multipart_to_sink() {
+        let (port, sink) = smtp_sink().await;
+
+        let notifier = EmailNotifier::new(
+            "email",
+            "127.0.0.1",
+            "Bot <bot@example.com>".parse().unwrap(),
+            vec!["team@example.com".parse().unwrap()],
+        )
+            .port(port)
+            .tls(SmtpTls::None)
+            .credentials("bot", "secret");
+        let report = notifier.notify(&Digest::new(vec![open_pull_request(1)], now())).await;
+
+        assert!(report.result.is_ok(), "{:?}", report.result);
+
+        let received = sink.await.unwrap();
+
+        assert!(received.contains("AUTH PLAIN"));
+        assert!(received.contains("MAIL FROM:<bot@example.com>"));
+        assert!(received.contains("RCPT TO:<team@example.com>"));
+        assert!(received.contains("Subject: 1 open pull request (2024-07-19)"));
+        assert!(received.contains("Content-Type: multipart/alternative"));
+        assert!(received.contains("Content-Type: text/plain; charset=utf-8"));
+        assert!(received.contains("Content-Type: text/html; charset=utf-8"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,161 @@
+use reqwest::{Response, StatusCode, header::{HeaderMap, RETRY_AFTER}};
+use serde::de::DeserializeOwned;
+use chrono::Utc;
+
+// Enough of a body to recognise an HTML error page or a proxy's reply.
+const SNIPPET_CHARS: usize = 200;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("{0}")]
+    Config(String),
+    #[error("authentication failed for {url} ({status}): {body}")]
+    Unauthorized { url: String, status: StatusCode, body: String },
+    #[error("not found: {url}")]
+    NotFound { url: String },
+    #[error("rate limited on {url}{}", retry_after.map(|seconds| format!(", retry after {}s", seconds)).unwrap_or_default())]
+    RateLimited { url: String, retry_after: Option<u64> },
+    #[error("{url} responded {status}: {body}")]
+    Status { url: String, status: StatusCode, body: String },
+    #[error("failed to decode response from {url}: {snippet}")]
+    Decode { url: String, snippet: String, #[source] source: serde_json::Error },
+    #[error("Slack rejected the message ({status}): {body}")]
+    SlackRejected { status: StatusCode, body: String },
+    #[error("request to {url} failed")]
+    Transport { url: String, #[source] source: reqwest::Error },
+}
+
+impl Error {
+    /// Classifies an unsuccessful response. GitHub signals an exhausted rate
+    /// limit with 403 rather than 429.
+    pub async fn from_response(url: &str, response: Response) -> Self {
+        let url = url.to_string();
+        let status = response.status();
+        let retry_after = retry_after(response.headers());
+        let exhausted = response.headers().get("x-ratelimit-remaining").is_some_and(|remaining| remaining == "0");
+        let body = snippet(&response.text().await.unwrap_or_default());
+
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited { url, retry_after },
+            StatusCode::FORBIDDEN if exhausted => Self::RateLimited { url, retry_after },
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Unauthorized { url, status, body },
+            StatusCode::NOT_FOUND => Self::NotFound { url },
+            _ => Self::Status { url, status, body },
+        }
+    }
+}
+
+/// Seconds from `Retry-After`, else until `X-RateLimit-Reset`.
+fn retry_after(headers: &HeaderMap) -> Option<u64> {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok()).and_then(|value| value.parse::<i64>().ok());
+
+    header(RETRY_AFTER.as_str())
+        .or_else(|| header("x-ratelimit-reset").map(|reset| reset - Utc::now().timestamp()))
+        .map(|seconds| seconds.max(0) as u64)
+}
+
+fn snippet(body: &str) -> String {
+    match body.char_indices().nth(SNIPPET_CHARS) {
+        Some((end, _)) => format!("{}…", &body[..end]),
+        None => body.to_string(),
+    }
+}
+
+/// The response when it was sent and succeeded, else why not.
+pub async fn check(url: &str, response: reqwest::Result<Response>) -> Result<Response, Error> {
+    let response = response.map_err(|source| Error::Transport { url: url.to_string(), source })?;
+
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        Err(Error::from_response(url, response).await)
+    }
+}
+
+/// The body of a successful response as `T`.
+pub async fn json<T: DeserializeOwned>(url: &str, response: Response) -> Result<T, Error> {
+    let body = response.text().await.map_err(|source| Error::Transport { url: url.to_string(), source })?;
+
+    serde_json::from_str::<T>(&body).map_err(|source| Error::Decode { url: url.to_string(), snippet: snippet(&body), source })
+}
+
+/// `check` then `json`.
+pub async fn decode<T: DeserializeOwned>(url: &str, response: reqwest::Result<Response>) -> Result<T, Error> {
+    json(url, check(url, response).await?).await
+}
+
+#[cfg(test)]
+mod error_test {
+    use rstest::rstest;
+
+    use super::*;
+
+    async fn get(server: &mut mockito::ServerGuard, status: usize, headers: &[(&str, &str)], body: &str) -> Result<serde_json::Value, Error> {
+        let mut mock = server.mock("GET", "/resource").with_status(status).with_body(body);
+        for (name, value) in headers {
+            mock = mock.with_header(*name, value);
+        }
+        mock.create_async().await;
+
+        let url = format!("{}/resource", server.url());
+        decode::<serde_json::Value>(&url, reqwest::get(&url).await).await
+    }
+
+    #[rstest]
+    #[case(401, vec![], "unauthorized")]
+    #[case(403, vec![], "unauthorized")]
+    #[case(403, vec![("x-ratelimit-remaining", "0"), ("retry-after", "30")], "rate_limited")]
+    #[case(429, vec![], "rate_limited")]
+    #[case(404, vec![], "not_found")]
+    #[case(502, vec![], "status")]
+    #[tokio::test]
+    async fn it_classifies_status(#[case] status: usize, #[case] headers: Vec<(&str, &str)>, #[case] expected: &str) {
+        let mut server = mockito::Server::new_async().await;
+
+        let error = get(&mut server, status, &headers, r#"{"message":"nope"}"#).await.unwrap_err();
+
+        let kind = match error {
+            Error::Unauthorized { status: code, body, .. } => {
+                assert_eq!(status as u16, code.as_u16());
+                assert_eq!(r#"{"message":"nope"}"#, body);
+                "unauthorized"
+            },
+            Error::RateLimited { retry_after, .. } => {
+                assert_eq!(headers.is_empty(), retry_after.is_none());
+                "rate_limited"
+            },
+            Error::NotFound { url } => {
+                assert_eq!(format!("{}/resource", server.url()), url);
+                "not_found"
+            },
+            Error::Status { .. } => "status",
+            other => panic!("unexpected {:?}", other),
+        };
+
+        assert_eq!(expected, kind);
+    }
+
+    #[tokio::test]
+    async fn it_reports_decode_error_with_snippet() {
+        let mut server = mockito::Server::new_async().await;
+        let body = format!("<html>{}</html>", "x".repeat(300));
+
+        match get(&mut server, 200, &[], &body).await.unwrap_err() {
+            Error::Decode { url, snippet, .. } => {
+                assert_eq!(format!("{}/resource", server.url()), url);
+                assert_eq!(SNIPPET_CHARS + 1, snippet.chars().count());
+                assert!(snippet.starts_with("<html>xxx"));
+            },
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_reports_transport_error() {
+        let url = "http://127.0.0.1:9/resource";
+
+        let error = decode::<serde_json::Value>(url, reqwest::get(url).await).await.unwrap_err();
+
+        assert!(matches!(error, Error::Transport { .. }));
+    }
+}
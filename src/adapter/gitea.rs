@@ -0,0 +1,267 @@
+use serde::Deserialize;
+use reqwest::{
+    Client,
+    header::{AUTHORIZATION, HeaderMap, HeaderValue}
+};
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::adapter::{error, source::ChangeRequestSource};
+use crate::domain::model::{
+    change_request::{ChangeRequest, Forge},
+    gitea::{GiteaPullRequest, GiteaRepository, GiteaReview},
+};
+
+// Gitea's default `MAX_RESPONSE_ITEMS`; instances may cap pages lower.
+const LIMIT: usize = 50;
+
+pub struct GiteaApi {
+    client: Client,
+    headers: HeaderMap,
+    api_uri: String,
+    repos: Vec<String>,
+}
+
+impl GiteaApi {
+    /// `uri` is the instance root, e.g. `https://forgejo.example.com`.
+    pub fn new(uri: &str, token: &str) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("token {}", token))?);
+
+        let client = reqwest::Client::new();
+
+        Ok(Self {
+            client,
+            headers,
+            api_uri: format!("{}/api/v1", uri.trim_end_matches('/')),
+            repos: Vec::new(),
+        })
+    }
+
+    /// `owner/repo` names; without any, every repository the token can see.
+    pub fn repos(mut self, repos: Vec<String>) -> Self {
+        self.repos = repos;
+        self
+    }
+
+    /// Pages through until `X-Total-Count` items have come back. A short page
+    /// is no sign of the end, since the instance may cap `limit`; without the
+    /// header only an empty page is.
+    pub async fn fetch<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<Vec<T>> {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        let mut items = Vec::new();
+
+        for page in 1.. {
+            let url = format!("{}/{}{}limit={}&page={}", self.api_uri, path, separator, LIMIT, page);
+            let response = error::check(&url, self.client.get(&url).headers(self.headers.clone()).send().await).await?;
+
+            let total = response.headers()
+                .get("X-Total-Count")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<usize>().ok());
+
+            let batch = error::json::<Vec<T>>(&url, response).await?;
+            let empty = batch.is_empty();
+
+            items.extend(batch);
+
+            if empty || total.is_some_and(|total| items.len() >= total) {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    async fn repositories(&self) -> Result<Vec<GiteaRepository>> {
+        if !self.repos.is_empty() {
+            return Ok(self.repos
+                .iter()
+                .map(|full_name| GiteaRepository {
+                    name: full_name.rsplit('/').next().unwrap_or(full_name).to_string(),
+                    full_name: full_name.to_string(),
+                })
+                .collect());
+        }
+
+        self.fetch::<GiteaRepository>("user/repos").await
+    }
+}
+
+#[async_trait]
+impl ChangeRequestSource for GiteaApi {
+    fn forge(&self) -> Forge {
+        Forge::Gitea
+    }
+
+    async fn change_requests(&self, repos: &[String]) -> Result<Vec<ChangeRequest>> {
+        let mut change_requests = Vec::new();
+
+        for repository in self.repositories().await? {
+            let repo = repository.repository(&self.api_uri);
+
+            if !repo.matches(repos) {
+                continue;
+            }
+
+            let pulls = self.fetch::<GiteaPullRequest>(&format!("repos/{}/pulls?state=open", repository.full_name)).await?;
+
+            for pull in pulls {
+                let reviews = self
+                    .fetch::<GiteaReview>(&format!("repos/{}/pulls/{}/reviews", repository.full_name, pull.number))
+                    .await?;
+
+                change_requests.push(pull.change_request(repo.clone(), &reviews)?);
+            }
+        }
+
+        Ok(change_requests)
+    }
+}
+
+#[cfg(test)]
+mod gitea_api_test {
+    use rstest::{rstest, fixture};
+
+    use super::*;
+    use crate::domain::model::change_request::ReviewVerdict;
+
+    #[fixture]
+    fn pull_request() -> serde_json::Value {
+        serde_json::json!({
+            "number": 3,
+            "title": "Add Gitea source",
+            "html_url": "https://forgejo.example.com/tools/gospo/pulls/3",
+            "state": "open",
+            "draft": false,
+            "user": { "login": "reo0306", "html_url": "https://forgejo.example.com/reo0306" },
+            "created_at": "2024-07-17T05:09:31+09:00",
+            "requested_reviewers": [
+                { "login": "test2", "html_url": "https://forgejo.example.com/test2" }
+            ]
+        })
+    }
+
+    fn user(login: &str) -> serde_json::Value {
+        serde_json::json!({ "login": login, "html_url": format!("https://forgejo.example.com/{}", login) })
+    }
+
+    async fn mock_server(server: &mut mockito::ServerGuard, path: &str, query: &str, body: String, total: Option<usize>) -> mockito::Mock {
+        let mut mock = server
+            .mock("GET", path)
+            .match_query(mockito::Matcher::Exact(query.to_string()))
+            .match_header("authorization", "token gitea-token")
+            .with_status(200)
+            .with_body(body);
+
+        if let Some(total) = total {
+            mock = mock.with_header("X-Total-Count", &total.to_string());
+        }
+
+        mock.create_async().await
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn it_change_requests(pull_request: serde_json::Value) {
+        let mut server = mockito::Server::new_async().await;
+        let gitea_api = GiteaApi::new(&server.url(), "gitea-token").unwrap();
+
+        let mocks = vec![
+            mock_server(
+                &mut server,
+                "/api/v1/user/repos",
+                "limit=50&page=1",
+                serde_json::json!([{ "name": "gospo", "full_name": "tools/gospo" }]).to_string(),
+                Some(1),
+            ).await,
+            mock_server(&mut server, "/api/v1/repos/tools/gospo/pulls", "state=open&limit=50&page=1", serde_json::json!([pull_request]).to_string(), Some(1)).await,
+            mock_server(
+                &mut server,
+                "/api/v1/repos/tools/gospo/pulls/3/reviews",
+                "limit=50&page=1",
+                serde_json::json!([
+                    { "user": user("test"), "state": "REQUEST_CHANGES" },
+                    { "user": user("test"), "state": "APPROVED" },
+                    { "user": user("test3"), "state": "REQUEST_CHANGES", "dismissed": true },
+                    { "user": null, "state": "REQUEST_REVIEW" },
+                ]).to_string(),
+                Some(4),
+            ).await,
+        ];
+
+        let change_requests = gitea_api.change_requests(&[]).await.unwrap();
+
+        assert_eq!(1, change_requests.len());
+
+        let pull = &change_requests[0];
+        assert_eq!(Forge::Gitea, pull.repo.forge);
+        assert_eq!("tools/gospo", pull.repo.full_name);
+        assert_eq!(format!("{}/api/v1/repos/tools/gospo", server.url()), pull.repo.url);
+        assert_eq!(3, pull.number);
+        assert_eq!("2024-07-16T20:09:31", pull.created_at.format("%Y-%m-%dT%H:%M:%S").to_string());
+        assert_eq!(
+            vec![("test2", ReviewVerdict::Pending), ("test", ReviewVerdict::Approved)],
+            pull.reviewers
+                .iter()
+                .map(|reviewer| (reviewer.user.login.as_str(), reviewer.verdict))
+                .collect::<Vec<(&str, ReviewVerdict)>>()
+        );
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    fn repositories(range: std::ops::Range<usize>) -> String {
+        serde_json::json!(
+            range.map(|i| serde_json::json!({ "name": format!("r{}", i), "full_name": format!("o/r{}", i) })).collect::<Vec<_>>()
+        ).to_string()
+    }
+
+    #[tokio::test]
+    async fn it_fetches_until_total_count() {
+        let mut server = mockito::Server::new_async().await;
+        let gitea_api = GiteaApi::new(&server.url(), "gitea-token").unwrap();
+
+        // An instance with `MAX_RESPONSE_ITEMS = 30` answers `limit=50` with 30.
+        let first = mock_server(&mut server, "/api/v1/user/repos", "limit=50&page=1", repositories(0..30), Some(45)).await;
+        let second = mock_server(&mut server, "/api/v1/user/repos", "limit=50&page=2", repositories(30..45), Some(45)).await;
+
+        assert_eq!(45, gitea_api.fetch::<GiteaRepository>("user/repos").await.unwrap().len());
+
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn it_fetches_until_empty_page_without_total_count() {
+        let mut server = mockito::Server::new_async().await;
+        let gitea_api = GiteaApi::new(&server.url(), "gitea-token").unwrap();
+
+        let first = mock_server(&mut server, "/api/v1/user/repos", "limit=50&page=1", repositories(0..30), None).await;
+        let second = mock_server(&mut server, "/api/v1/user/repos", "limit=50&page=2", "[]".to_string(), None).await;
+
+        assert_eq!(30, gitea_api.fetch::<GiteaRepository>("user/repos").await.unwrap().len());
+
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn it_uses_configured_repos(pull_request: serde_json::Value) {
+        let mut server = mockito::Server::new_async().await;
+        let gitea_api = GiteaApi::new(&server.url(), "gitea-token").unwrap().repos(vec!["tools/gospo".to_string()]);
+
+        let pulls = mock_server(&mut server, "/api/v1/repos/tools/gospo/pulls", "state=open&limit=50&page=1", serde_json::json!([pull_request]).to_string(), Some(1)).await;
+        let reviews = mock_server(&mut server, "/api/v1/repos/tools/gospo/pulls/3/reviews", "limit=50&page=1", "[]".to_string(), Some(0)).await;
+
+        let change_requests = gitea_api.change_requests(&["gospo".to_string()]).await.unwrap();
+
+        assert_eq!(vec!["test2"], change_requests[0].text_line().unapproved_reviewer_logins());
+
+        pulls.assert_async().await;
+        reviews.assert_async().await;
+    }
+}
@@ -2,64 +2,211 @@ use std::env;
 use serde::Deserialize;
 use reqwest::{
     Client,
-    header::{ACCEPT, AUTHORIZATION, USER_AGENT, HeaderMap, HeaderValue}
+    header::{ACCEPT, AUTHORIZATION, LINK, USER_AGENT, HeaderMap, HeaderValue}
 };
 use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::adapter::{error, github_app::GithubApp, secret, source::ChangeRequestSource};
+use crate::domain::model::{
+    change_request::{self, ChangeRequest, Forge},
+    github::{InstallationRepositories, OpenPullRequest, PullRequest, Repository, Review, Reviewers, User},
+};
+
+pub const GITHUB_API_URI: &str = "https://api.github.com";
+pub const GITHUB_GRAPHQL_URI: &str = "https://api.github.com/graphql";
+pub const PER_PAGE: u32 = 100;
+
+/// REST root for a GitHub host given as its web root (`https://github.com`,
+/// `https://github.example.com`) or as an API root already.
+pub fn api_uri_for(uri: &str) -> String {
+    let uri = uri.trim_end_matches('/');
+
+    match uri.split_once("://").map_or(uri, |(_, host)| host) {
+        "github.com" | "api.github.com" => GITHUB_API_URI.to_string(),
+        host if host.ends_with("/api/v3") => uri.to_string(),
+        _ => format!("{}/api/v3", uri),
+    }
+}
+
+/// GraphQL endpoint for the same host: `/graphql` on api.github.com, and
+/// `/api/graphql` beside `/api/v3` on Enterprise Server.
+pub fn graphql_uri_for(uri: &str) -> String {
+    let api_uri = api_uri_for(uri);
+
+    match api_uri.strip_suffix("/api/v3") {
+        Some(root) => format!("{}/api/graphql", root),
+        None => GITHUB_GRAPHQL_URI.to_string(),
+    }
+}
+
+/// The `rel="next"` target of a `Link` header, absent on the last page.
+pub fn next_link(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(LINK)?
+        .to_str()
+        .ok()?
+        .split(',')
+        .find_map(|link| {
+            let (target, params) = link.split_once(';')?;
+            params
+                .split(';')
+                .any(|param| param.trim() == "rel=\"next\"")
+                .then(|| target.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+        })
+}
+
+pub enum GithubAuth {
+    /// Personal access token.
+    Token(String),
+    App(Box<GithubApp>),
+}
+
+impl GithubAuth {
+    async fn authorization(&self) -> Result<String> {
+        let token = match self {
+            Self::Token(token) => token.to_string(),
+            Self::App(app) => app.token().await?,
+        };
+
+        Ok(format!("Bearer {}", token))
+    }
+}
 
 pub struct GithubApi {
     client: Client,
     headers: HeaderMap,
+    auth: GithubAuth,
+    api_uri: String,
 }
 
 impl GithubApi {
-    pub fn new() -> Self {
-        let token = env::var("GITHUB_TOKEN").unwrap();
+    /// github.com, or the Enterprise Server GitHub Actions points
+    /// `GITHUB_API_URL` at, with `GITHUB_TOKEN`.
+    pub fn new() -> Result<Self> {
+        let api = Self::with_token(&secret::var("GITHUB_TOKEN")?);
+
+        Ok(match env::var("GITHUB_API_URL") {
+            Ok(api_uri) => api.api_uri(&api_uri),
+            Err(_) => api,
+        })
+    }
+
+    pub fn with_token(token: &str) -> Self {
+        Self::with_auth(GithubAuth::Token(token.to_string()))
+    }
 
+    pub fn with_app(app: GithubApp) -> Self {
+        Self::with_auth(GithubAuth::App(Box::new(app)))
+    }
+
+    fn with_auth(auth: GithubAuth) -> Self {
         let mut headers = HeaderMap::new();
         headers.insert(USER_AGENT, HeaderValue::from_static("request"));
         headers.insert(ACCEPT, HeaderValue::from_static("application/vnd.github.v3+json"));
-        headers.insert(AUTHORIZATION, HeaderValue::from_str(&format!("token {}", token)).unwrap());
         headers.insert("X-GitHub-Api-Version", HeaderValue::from_static("2022-11-28"));
 
         let client = reqwest::Client::new();
 
-        Self { client, headers }
+        Self { client, headers, auth, api_uri: GITHUB_API_URI.to_string() }
+    }
+
+    pub fn api_uri(mut self, api_uri: &str) -> Self {
+        self.api_uri = api_uri.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// The account the token belongs to.
+    pub async fn authenticated_user(&self) -> Result<User> {
+        self.find::<User>(&format!("{}/user", self.api_uri)).await
     }
 
     pub async fn fetch<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<Vec<T>> {
-        let response = self.client
-            .get(url)
-            .headers(self.headers.clone())
-            .send()
-            .await?;
+        self.find::<Vec<T>>(url).await
+    }
 
-        match response.error_for_status_ref() {
-            Ok(_) => {
-                let repos = response.json::<Vec<T>>().await?;
-                Ok(repos)
-            },
-            Err(e) => {
-                Err(e.into())
-            }
+    /// Repositories the app installation was granted, following
+    /// `Link: rel="next"` until the last page.
+    pub async fn installation_repositories(&self) -> Result<Vec<Repository>> {
+        let mut repositories = Vec::new();
+        let mut next = Some(format!("{}/installation/repositories?per_page={}", self.api_uri, PER_PAGE));
+
+        while let Some(url) = next {
+            let response = self.client
+                .get(&url)
+                .headers(self.headers.clone())
+                .header(AUTHORIZATION, self.auth.authorization().await?)
+                .send()
+                .await;
+            let response = error::check(&url, response).await?;
+
+            next = next_link(response.headers());
+            repositories.extend(error::json::<InstallationRepositories>(&url, response).await?.repositories);
         }
+
+        Ok(repositories)
     }
 
     pub async fn find<T: for<'de> Deserialize<'de>>(&self, url: &str) -> Result<T> {
         let response = self.client
             .get(url)
             .headers(self.headers.clone())
+            .header(AUTHORIZATION, self.auth.authorization().await?)
             .send()
-            .await?;
+            .await;
 
-        match response.error_for_status_ref() {
-            Ok(_) => {
-                let repos = response.json::<T>().await?;
-                Ok(repos)
-            },
-            Err(e) => {
-                Err(e.into())
+        Ok(error::decode::<T>(url, response).await?)
+    }
+}
+
+#[async_trait]
+impl ChangeRequestSource for GithubApi {
+    fn forge(&self) -> Forge {
+        Forge::Github
+    }
+
+    async fn check(&self) -> Result<String> {
+        match &self.auth {
+            GithubAuth::Token(_) => Ok(format!("authenticated as {}", self.authenticated_user().await?.login)),
+            GithubAuth::App(_) => Ok(format!(
+                "installation granted {} repositories",
+                self.installation_repositories().await?.len()
+            )),
+        }
+    }
+
+    async fn change_requests(&self, repos: &[String]) -> Result<Vec<ChangeRequest>> {
+        // Installation tokens have no user; they see what the app was granted.
+        let repositories = match &self.auth {
+            GithubAuth::Token(_) => self.fetch::<Repository>(&format!("{}/user/repos?page=3", self.api_uri)).await?,
+            GithubAuth::App(_) => self.installation_repositories().await?,
+        };
+
+        let mut change_requests = Vec::new();
+
+        for repo in repositories {
+            if !change_request::Repository::from(repo.clone()).matches(repos) {
+                continue;
+            }
+
+            let pulls = self.fetch::<PullRequest>(&format!("{}/pulls?state=open", repo.url)).await?;
+
+            for pull in pulls {
+                let requested_reviewers = self
+                    .find::<Reviewers>(&format!("{}/pulls/{}/requested_reviewers", &repo.url, pull.number))
+                    .await?;
+
+                let reviews = if requested_reviewers.users.is_empty() {
+                    Vec::new()
+                } else {
+                    self.fetch::<Review>(&format!("{}/pulls/{}/reviews", &repo.url, pull.number)).await?
+                };
+
+                change_requests.push(OpenPullRequest::new(&repo, pull, requested_reviewers, reviews).try_into()?);
             }
         }
+
+        Ok(change_requests)
     }
 }
 
@@ -68,6 +215,7 @@ mod github_api_test {
     use rstest::{rstest, fixture};
 
     use super::*;
+    use crate::domain::model::change_request::ReviewVerdict;
     use crate::domain::model::github::{
         PullRequest,
         Repository,
@@ -78,8 +226,7 @@ mod github_api_test {
 
     #[fixture]
     fn github_api() -> GithubApi {
-        dotenvy::dotenv().unwrap();
-        GithubApi::new()
+        GithubApi::with_token("aaaaaaaa")
     }
 
     #[rstest]
@@ -124,8 +271,10 @@ mod github_api_test {
             user: User {
                 login: "test".to_string(),
                 html_url: "https://github.com/reo0306".to_string(),
+                avatar_url: None,
             },
             created_at: "2024-07-16T20:09:31Z".to_string(),
+            draft: false,
         },
         "/repos/reo0306/gospo/pulls",
     )]
@@ -163,6 +312,7 @@ mod github_api_test {
                 User {
                     login: "test".to_string(),
                     html_url: "https://github.com/reo0306".to_string(),
+                    avatar_url: None,
                 },
             ],
         },
@@ -194,6 +344,7 @@ mod github_api_test {
             user: User {
                 login: "test".to_string(),
                 html_url: "https://github.com/reo0306".to_string(),
+                avatar_url: None,
             },
             state: "APPROVED".to_string(),
         },
@@ -221,13 +372,136 @@ mod github_api_test {
         mock.assert_async().await;
     }
 
+    #[rstest]
+    #[case(Vec::new(), 1)]
+    #[case(vec!["gospo".to_string()], 1)]
+    #[case(vec!["reo0306/other".to_string()], 0)]
+    #[tokio::test]
+    async fn it_change_requests(github_api: GithubApi, #[case] repos: Vec<String>, #[case] expected: usize) {
+        let mut server = mockito::Server::new_async().await;
+        let github_api = github_api.api_uri(&server.url());
+        let repo_url = format!("{}/repos/reo0306/gospo", server.url());
+        let user = |login: &str| serde_json::json!({ "login": login, "html_url": format!("https://github.com/{}", login) });
+
+        let mocks = vec![
+            server.mock("GET", "/user/repos?page=3")
+                .with_body(serde_json::json!([{ "name": "gospo", "full_name": "reo0306/gospo", "url": repo_url }]).to_string())
+                .create_async().await,
+            server.mock("GET", "/repos/reo0306/gospo/pulls?state=open")
+                .with_body(serde_json::json!([{
+                    "html_url": "https://github.com/reo0306/gospo/pull/1",
+                    "number": 1,
+                    "state": "open",
+                    "title": "Test",
+                    "user": user("reo0306"),
+                    "created_at": "2024-07-16T20:09:31Z",
+                    "draft": true
+                }]).to_string())
+                .expect(expected)
+                .create_async().await,
+            server.mock("GET", "/repos/reo0306/gospo/pulls/1/requested_reviewers")
+                .with_body(serde_json::json!({ "users": [user("test"), user("test2")] }).to_string())
+                .expect(expected)
+                .create_async().await,
+            server.mock("GET", "/repos/reo0306/gospo/pulls/1/reviews")
+                .with_body(serde_json::json!([
+                    { "user": user("test"), "state": "COMMENTED" },
+                    { "user": user("test"), "state": "APPROVED" }
+                ]).to_string())
+                .expect(expected)
+                .create_async().await,
+        ];
+
+        let change_requests = github_api.change_requests(&repos).await.unwrap();
+
+        assert_eq!(expected, change_requests.len());
+
+        if let Some(change_request) = change_requests.first() {
+            assert_eq!(Forge::Github, change_request.repo.forge);
+            assert_eq!("reo0306/gospo", change_request.repo.full_name);
+            assert!(change_request.draft);
+            assert_eq!(
+                vec![("test", ReviewVerdict::Approved), ("test2", ReviewVerdict::Pending)],
+                change_request.reviewers
+                    .iter()
+                    .map(|reviewer| (reviewer.user.login.as_str(), reviewer.verdict))
+                    .collect::<Vec<(&str, ReviewVerdict)>>()
+            );
+        }
+
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[rstest]
+    #[case("https://github.com", "https://api.github.com", "https://api.github.com/graphql")]
+    #[case("https://api.github.com/", "https://api.github.com", "https://api.github.com/graphql")]
+    #[case("https://github.example.com", "https://github.example.com/api/v3", "https://github.example.com/api/graphql")]
+    #[case("https://github.example.com/api/v3/", "https://github.example.com/api/v3", "https://github.example.com/api/graphql")]
+    fn it_api_uri_for(#[case] uri: &str, #[case] api_uri: &str, #[case] graphql_uri: &str) {
+        assert_eq!(api_uri, api_uri_for(uri));
+        assert_eq!(graphql_uri, graphql_uri_for(uri));
+    }
+
+    #[rstest]
+    #[case(r#"<https://api.github.com/installation/repositories?page=2>; rel="next", <https://api.github.com/installation/repositories?page=3>; rel="last""#, Some("https://api.github.com/installation/repositories?page=2"))]
+    #[case(r#"<https://api.github.com/installation/repositories?page=1>; rel="prev""#, None)]
+    #[case("", None)]
+    fn it_next_link(#[case] link: &str, #[case] expected: Option<&str>) {
+        let mut headers = HeaderMap::new();
+        headers.insert(LINK, HeaderValue::from_str(link).unwrap());
+
+        assert_eq!(expected.map(str::to_string), next_link(&headers));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn it_authenticated_user_on_enterprise_server(github_api: GithubApi) {
+        let server = mockito::Server::new_async().await;
+        let body = serde_json::json!({ "login": "reo0306", "html_url": format!("{}/reo0306", server.url()) }).to_string();
+        let (server, mock) = mock_server(server, "/api/v3/user", body).await;
+
+        let user = github_api.api_uri(&api_uri_for(&server.url())).authenticated_user().await.unwrap();
+
+        assert_eq!("reo0306", user.login);
+
+        mock.assert_async().await;
+    }
+
+    #[rstest]
+    #[case(401, &[], "Bad credentials")]
+    #[case(403, &[("x-ratelimit-remaining", "0"), ("x-ratelimit-reset", "0")], "API rate limit exceeded")]
+    #[case(404, &[], "Not Found")]
+    #[tokio::test]
+    async fn it_change_requests_error(github_api: GithubApi, #[case] status: usize, #[case] headers: &[(&str, &str)], #[case] message: &str) {
+        let mut server = mockito::Server::new_async().await;
+        let github_api = github_api.api_uri(&server.url());
+        let mut mock = server.mock("GET", "/user/repos?page=3")
+            .with_status(status)
+            .with_body(serde_json::json!({ "message": message }).to_string());
+        for (name, value) in headers {
+            mock = mock.with_header(*name, value);
+        }
+        mock.create_async().await;
+
+        let error = github_api.change_requests(&[]).await.unwrap_err();
+
+        match (status, error.downcast_ref::<error::Error>()) {
+            (401, Some(error::Error::Unauthorized { body, .. })) => assert!(body.contains(message)),
+            (403, Some(error::Error::RateLimited { retry_after, .. })) => assert_eq!(&Some(0), retry_after),
+            (404, Some(error::Error::NotFound { url })) => assert_eq!(&format!("{}/user/repos?page=3", server.url()), url),
+            (_, other) => panic!("unexpected {:?}", other),
+        }
+    }
+
     async fn mock_server(mut server: mockito::ServerGuard, path: &str, body: String) -> (mockito::ServerGuard, mockito::Mock) {
         let mock = server
             .mock("GET", path)
             .with_status(200)
             .with_header("User-Agent", "request")
             .with_header("Accept", "application/vnd.github.v3+json")
-            .with_header("Authorization", "token aaaaaaaa")
+            .with_header("Authorization", "Bearer aaaaaaaa")
             .with_header("X-GitHub-Api-Version", "2022-11-28")
             .with_body(body)
             .create_async()
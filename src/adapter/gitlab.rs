@@ -0,0 +1,269 @@
+use std::collections::HashSet;
+use serde::Deserialize;
+use reqwest::{
+    Client,
+    header::{HeaderMap, HeaderValue}
+};
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::adapter::{error, source::ChangeRequestSource};
+use crate::domain::model::{
+    change_request::{ChangeRequest, Forge},
+    gitlab::{Approvals, MergeRequest},
+};
+
+const PER_PAGE: u32 = 100;
+
+pub struct GitlabApi {
+    client: Client,
+    headers: HeaderMap,
+    api_uri: String,
+    projects: Vec<String>,
+    groups: Vec<String>,
+}
+
+impl GitlabApi {
+    /// `uri` is the instance root, e.g. `https://gitlab.example.com`.
+    pub fn new(uri: &str, token: &str) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert("PRIVATE-TOKEN", HeaderValue::from_str(token)?);
+
+        let client = reqwest::Client::new();
+
+        Ok(Self {
+            client,
+            headers,
+            api_uri: format!("{}/api/v4", uri.trim_end_matches('/')),
+            projects: Vec::new(),
+            groups: Vec::new(),
+        })
+    }
+
+    /// Project paths (`group/project`) or ids.
+    pub fn projects(mut self, projects: Vec<String>) -> Self {
+        self.projects = projects;
+        self
+    }
+
+    /// Group paths or ids, subgroups included.
+    pub fn groups(mut self, groups: Vec<String>) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// Follows `X-Next-Page` until GitLab leaves it empty.
+    pub async fn fetch<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<Vec<T>> {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        let mut items = Vec::new();
+        let mut page = "1".to_string();
+
+        loop {
+            let url = format!("{}/{}{}per_page={}&page={}", self.api_uri, path, separator, PER_PAGE, page);
+            let response = error::check(&url, self.client.get(&url).headers(self.headers.clone()).send().await).await?;
+
+            let next_page = response.headers()
+                .get("X-Next-Page")
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_string())
+                .unwrap_or_default();
+
+            items.extend(error::json::<Vec<T>>(&url, response).await?);
+
+            if next_page.is_empty() {
+                return Ok(items);
+            }
+            page = next_page;
+        }
+    }
+
+    pub async fn find<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T> {
+        let url = format!("{}/{}", self.api_uri, path);
+        let response = self.client.get(&url).headers(self.headers.clone()).send().await;
+
+        Ok(error::decode::<T>(&url, response).await?)
+    }
+
+    /// `group/subgroup/project` is a single path segment in the API.
+    fn encode(path: &str) -> String {
+        path.replace('/', "%2F")
+    }
+}
+
+#[async_trait]
+impl ChangeRequestSource for GitlabApi {
+    fn forge(&self) -> Forge {
+        Forge::Gitlab
+    }
+
+    /// Each merge request once, even when its project is also in a listed
+    /// group.
+    async fn change_requests(&self, repos: &[String]) -> Result<Vec<ChangeRequest>> {
+        let mut merge_requests = Vec::new();
+
+        for project in &self.projects {
+            merge_requests.extend(
+                self.fetch::<MergeRequest>(&format!("projects/{}/merge_requests?state=opened", Self::encode(project))).await?
+            );
+        }
+        for group in &self.groups {
+            merge_requests.extend(
+                self.fetch::<MergeRequest>(&format!("groups/{}/merge_requests?state=opened", Self::encode(group))).await?
+            );
+        }
+
+        let mut seen = HashSet::new();
+        let mut change_requests = Vec::new();
+
+        for merge_request in merge_requests.into_iter().filter(|merge_request| seen.insert(merge_request.id)) {
+            if !merge_request.repository(&self.api_uri).matches(repos) {
+                continue;
+            }
+
+            let approvals = self
+                .find::<Approvals>(&format!("projects/{}/merge_requests/{}/approvals", merge_request.project_id, merge_request.iid))
+                .await?;
+
+            change_requests.push(merge_request.change_request(&self.api_uri, &approvals)?);
+        }
+
+        Ok(change_requests)
+    }
+}
+
+#[cfg(test)]
+mod gitlab_api_test {
+    use rstest::{rstest, fixture};
+
+    use super::*;
+    use crate::domain::model::change_request::ReviewVerdict;
+
+    #[fixture]
+    fn merge_request() -> serde_json::Value {
+        serde_json::json!({
+            "id": 101,
+            "iid": 7,
+            "project_id": 42,
+            "title": "Add GitLab source",
+            "web_url": "https://gitlab.example.com/platform/tools/gospo/-/merge_requests/7",
+            "state": "opened",
+            "draft": true,
+            "author": {
+                "username": "reo0306",
+                "web_url": "https://gitlab.example.com/reo0306",
+                "avatar_url": null
+            },
+            "reviewers": [
+                { "username": "test", "web_url": "https://gitlab.example.com/test" },
+                { "username": "test2", "web_url": "https://gitlab.example.com/test2" }
+            ],
+            "created_at": "2024-07-16T20:09:31.000Z",
+            "references": { "full": "platform/tools/gospo!7" }
+        })
+    }
+
+    async fn mock_server(server: &mut mockito::ServerGuard, path: &str, query: &str, body: String, next_page: &str) -> mockito::Mock {
+        server
+            .mock("GET", path)
+            .match_query(mockito::Matcher::Exact(query.to_string()))
+            .match_header("private-token", "gitlab-token")
+            .with_status(200)
+            .with_header("X-Next-Page", next_page)
+            .with_body(body)
+            .create_async()
+            .await
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn it_follows_next_page(merge_request: serde_json::Value) {
+        let mut server = mockito::Server::new_async().await;
+        let gitlab_api = GitlabApi::new(&server.url(), "gitlab-token").unwrap();
+
+        let path = "/api/v4/projects/platform%2Ftools%2Fgospo/merge_requests";
+        let first = mock_server(&mut server, path, "state=opened&per_page=100&page=1", serde_json::json!([merge_request]).to_string(), "2").await;
+        let second = mock_server(&mut server, path, "state=opened&per_page=100&page=2", serde_json::json!([merge_request]).to_string(), "").await;
+
+        let merge_requests = gitlab_api
+            .fetch::<MergeRequest>("projects/platform%2Ftools%2Fgospo/merge_requests?state=opened")
+            .await
+            .unwrap();
+
+        assert_eq!(2, merge_requests.len());
+
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn it_change_requests(merge_request: serde_json::Value) {
+        let mut server = mockito::Server::new_async().await;
+        let gitlab_api = GitlabApi::new(&server.url(), "gitlab-token")
+            .unwrap()
+            .projects(vec!["platform/tools/gospo".to_string()])
+            .groups(vec!["platform".to_string()]);
+
+        let body = serde_json::json!([merge_request]).to_string();
+        let project = mock_server(&mut server, "/api/v4/projects/platform%2Ftools%2Fgospo/merge_requests", "state=opened&per_page=100&page=1", body.clone(), "").await;
+        let group = mock_server(&mut server, "/api/v4/groups/platform/merge_requests", "state=opened&per_page=100&page=1", body, "").await;
+        let approvals = server
+            .mock("GET", "/api/v4/projects/42/merge_requests/7/approvals")
+            .match_header("private-token", "gitlab-token")
+            .with_status(200)
+            .with_body(serde_json::json!({
+                "approved_by": [
+                    { "user": { "username": "test", "web_url": "https://gitlab.example.com/test" } },
+                    { "user": { "username": "lead", "web_url": "https://gitlab.example.com/lead" } }
+                ]
+            }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let pulls = gitlab_api.change_requests(&[]).await.unwrap();
+
+        assert_eq!(1, pulls.len());
+
+        let pull = &pulls[0];
+        assert_eq!(Forge::Gitlab, pull.repo.forge);
+        assert_eq!("gospo", pull.repo.name);
+        assert_eq!("platform/tools/gospo", pull.repo.full_name);
+        assert_eq!(format!("{}/api/v4/projects/42", server.url()), pull.repo.url);
+        assert_eq!(7, pull.number);
+        assert_eq!("open", pull.state);
+        assert!(pull.draft);
+        assert_eq!("reo0306", pull.user.login);
+        assert_eq!("2024-07-16T20:09:31", pull.created_at.format("%Y-%m-%dT%H:%M:%S").to_string());
+        assert_eq!(
+            vec![("test", ReviewVerdict::Approved), ("test2", ReviewVerdict::Pending), ("lead", ReviewVerdict::Approved)],
+            pull.text_line()
+                .reviewer_states()
+                .iter()
+                .map(|(user, state)| (user.login.as_str(), *state))
+                .collect::<Vec<(&str, ReviewVerdict)>>()
+        );
+
+        project.assert_async().await;
+        group.assert_async().await;
+        approvals.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn it_fails_on_unauthorized() {
+        let mut server = mockito::Server::new_async().await;
+        let gitlab_api = GitlabApi::new(&server.url(), "wrong").unwrap().groups(vec!["platform".to_string()]);
+
+        let mock = server
+            .mock("GET", "/api/v4/groups/platform/merge_requests")
+            .match_query(mockito::Matcher::Any)
+            .with_status(401)
+            .with_body(r#"{"message":"401 Unauthorized"}"#)
+            .create_async()
+            .await;
+
+        assert!(gitlab_api.change_requests(&[]).await.is_err());
+
+        mock.assert_async().await;
+    }
+}
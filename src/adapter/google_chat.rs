@@ -0,0 +1,269 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::adapter::{
+    error::Error,
+    html,
+    notifier::{self, Notifier, Payload, Rendered},
+};
+use crate::domain::model::{
+    change_request::TextLine,
+    digest::Digest,
+    google_chat::{GoogleChat, CardWithId, Card, CardHeader, Section, Widget, Thread},
+    locale::{Label, Locale},
+};
+
+// Google Chat rejects messages above 32KB; 25 pull requests with
+// full-length multibyte titles stay under it.
+const MAX_PULLS: usize = 25;
+const CARD_ID: &str = "pull-request-digest";
+// Post into the digest thread when it exists, otherwise start it.
+const MESSAGE_REPLY_OPTION: &str = "REPLY_MESSAGE_FALLBACK_TO_NEW_THREAD";
+
+pub struct GoogleChatApi;
+
+impl GoogleChatApi {
+    pub fn construct_digest(digest: &Digest, locale: Locale) -> GoogleChat {
+        let now = digest.generated_at;
+        let text_lines = digest.pulls
+            .iter()
+            .map(|pull| pull.text_line().locale(locale))
+            .collect::<Vec<TextLine>>();
+
+        let mut sections = text_lines
+            .iter()
+            .take(MAX_PULLS)
+            .map(|text_line| Self::pull_request_section(text_line, now))
+            .collect::<Vec<Section>>();
+
+        if text_lines.len() > MAX_PULLS {
+            sections.push(Section::new(None, vec![Widget::paragraph(&locale.more(text_lines.len() - MAX_PULLS))]));
+        }
+
+        GoogleChat {
+            text: locale.open_pull_requests(text_lines.len()),
+            cards_v2: vec![
+                CardWithId {
+                    card_id: CARD_ID.to_string(),
+                    card: Card {
+                        header: CardHeader {
+                            title: locale.label(Label::OpenPullRequest).to_string(),
+                            subtitle: Some(locale.open_pull_requests(text_lines.len())),
+                        },
+                        sections,
+                    },
+                }
+            ],
+            thread: Some(Thread { thread_key: Self::thread_key(digest.local_date()) }),
+        }
+    }
+
+    /// One thread per local day, so repeated runs of the same digest stay
+    /// together.
+    pub fn thread_key(date: NaiveDate) -> String {
+        format!("pull-request-digest-{}", date)
+    }
+
+    fn pull_request_section(text_line: &TextLine, now: NaiveDateTime) -> Section {
+        let locale = text_line.locale;
+        let pull = text_line.pull;
+
+        let reviewers = text_line
+            .reviewer_states()
+            .iter()
+            .map(|(reviewer, state)| format!("{} {}", state.emoji(), html::escape(&reviewer.login)))
+            .collect::<Vec<String>>();
+
+        Section::new(
+            Some(format!("{}#{}", html::escape(&text_line.repo.full_name), pull.number)),
+            vec![
+                Widget::paragraph(&format!(
+                    "<b>{}</b>",
+                    html::link(&pull.html_url, &pull.short_title())
+                )),
+                Widget::decorated(locale.label(Label::Status), &html::escape(&pull.state)),
+                Widget::decorated(
+                    locale.label(Label::Reviewers),
+                    &if reviewers.is_empty() { locale.label(Label::NoReviewers).to_string() } else { reviewers.join(", ") }
+                ),
+                Widget::paragraph(&format!(
+                    "<font color=\"#80868b\">{} · {}</font>",
+                    locale.created_by(&html::link(&pull.user.html_url, &pull.user.login), &text_line.created_at()),
+                    locale.relative_age(&text_line.age(now))
+                )),
+                Widget::link_button(locale.label(Label::OpenButton), &pull.html_url),
+            ],
+        )
+    }
+
+    pub async fn post(webhook_uri: &str, chat: &GoogleChat) -> Result<(), Error> {
+        let mut request = reqwest::Client::new().post(webhook_uri).json(chat);
+
+        if let Some(Thread { thread_key }) = &chat.thread {
+            request = request.query(&[("threadKey", thread_key.as_str()), ("messageReplyOption", MESSAGE_REPLY_OPTION)]);
+        }
+
+        notifier::send("Google Chat webhook", request).await?;
+
+        Ok(())
+    }
+}
+
+pub struct GoogleChatNotifier {
+    name: String,
+    webhook_uri: String,
+    locale: Locale,
+}
+
+impl GoogleChatNotifier {
+    pub fn new(name: &str, webhook_uri: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            webhook_uri: webhook_uri.to_string(),
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+#[async_trait]
+impl Notifier for GoogleChatNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, digest: &Digest) -> anyhow::Result<Rendered> {
+        let chat = GoogleChatApi::construct_digest(digest, self.locale);
+
+        Ok(Rendered {
+            text: chat.text.clone(),
+            payload: Payload::GoogleChat(chat),
+        })
+    }
+
+    async fn send(&self, rendered: &Rendered) -> anyhow::Result<()> {
+        let Payload::GoogleChat(chat) = &rendered.payload else {
+            return Err(notifier::unexpected_payload(&self.name));
+        };
+
+        Ok(GoogleChatApi::post(&self.webhook_uri, chat).await?)
+    }
+}
+
+#[cfg(test)]
+mod google_chat_test {
+    use chrono_tz::Tz;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::domain::model::change_request::{
+        ChangeRequest,
+        fixture::{now, open_pull_request, open_pull_request_titled},
+    };
+
+    #[test]
+    fn test_construct_digest() {
+        let chat = GoogleChatApi::construct_digest(&Digest::new(vec![open_pull_request(1)], now()), Locale::En);
+
+        assert_eq!(
+            serde_json::json!({
+                "text": "1 open pull request",
+                "thread": { "threadKey": "pull-request-digest-2024-07-19" },
+                "cardsV2": [{
+                    "cardId": "pull-request-digest",
+                    "card": {
+                        "header": { "title": "Open Pull Request", "subtitle": "1 open pull request" },
+                        "sections": [{
+                            "header": "reo0306/gospo#1",
+                            "widgets": [
+                                { "textParagraph": { "text": "<b><a href=\"https://github.com/reo0306/gospo/pull/1\">Test</a></b>" } },
+                                { "decoratedText": { "topLabel": "Status", "text": "open" } },
+                                { "decoratedText": { "topLabel": "Reviewers", "text": "✅ test, ⏳ test2" } },
+                                { "textParagraph": { "text": "<font color=\"#80868b\">Created by <a href=\"https://github.com/reo0306\">reo0306</a> on 2024-07-16 20:09:31 · 2 days ago</font>" } },
+                                { "buttonList": { "buttons": [
+                                    { "text": "Open PR", "onClick": { "openLink": { "url": "https://github.com/reo0306/gospo/pull/1" } } }
+                                ] } },
+                            ]
+                        }]
+                    }
+                }]
+            }),
+            serde_json::json!(chat)
+        );
+    }
+
+    #[test]
+    fn test_construct_digest_escapes_untrusted_data() {
+        let chat = GoogleChatApi::construct_digest(
+            &Digest::new(vec![open_pull_request_titled("<a href=\"https://evil.example\">x</a>")], now()),
+            Locale::En,
+        );
+
+        assert_eq!(
+            Widget::paragraph(
+                "<b><a href=\"https://github.com/reo0306/gospo/pull/1\">&lt;a href=&quot;https://evil.example&quot;&gt;x&lt;/a&gt;</a></b>"
+            ),
+            chat.cards_v2[0].card.sections[0].widgets[0]
+        );
+    }
+
+    #[rstest]
+    #[case("2024-07-19T08:09:31Z", Tz::UTC, "pull-request-digest-2024-07-19")]
+    #[case("2024-07-19T23:59:59Z", Tz::UTC, "pull-request-digest-2024-07-19")]
+    #[case("2024-07-20T00:00:00Z", Tz::UTC, "pull-request-digest-2024-07-20")]
+    // 08:30 on the 19th in Tokyo is still the 18th in UTC.
+    #[case("2024-07-18T23:30:00Z", Tz::Asia__Tokyo, "pull-request-digest-2024-07-19")]
+    #[case("2024-07-19T03:00:00Z", Tz::America__Los_Angeles, "pull-request-digest-2024-07-18")]
+    fn test_thread_key(#[case] now: &str, #[case] timezone: Tz, #[case] expected: &str) {
+        let now = NaiveDateTime::parse_from_str(now, "%Y-%m-%dT%H:%M:%SZ").unwrap();
+
+        let chat = GoogleChatApi::construct_digest(&Digest::new(Vec::new(), now).timezone(timezone), Locale::En);
+
+        assert_eq!(expected, chat.thread.unwrap().thread_key);
+    }
+
+    #[rstest]
+    #[case(200, true)]
+    #[case(400, false)]
+    #[tokio::test]
+    async fn test_google_chat_notifier_notify(#[case] status: usize, #[case] ok: bool) {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/spaces/AAAA/messages")
+            .match_query(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("key".to_string(), "k".to_string()),
+                mockito::Matcher::UrlEncoded("threadKey".to_string(), "pull-request-digest-2024-07-19".to_string()),
+                mockito::Matcher::UrlEncoded("messageReplyOption".to_string(), MESSAGE_REPLY_OPTION.to_string()),
+            ]))
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"thread":{"threadKey":"pull-request-digest-2024-07-19"}}"#.to_string()
+            ))
+            .with_status(status)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let notifier = GoogleChatNotifier::new("chat", &format!("{}/v1/spaces/AAAA/messages?key=k", server.url()));
+        let report = notifier.notify(&Digest::new(vec![open_pull_request(1)], now())).await;
+
+        assert_eq!(ok, report.result.is_ok());
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_construct_digest_fits_size_limit() {
+        let limit = 32 * 1024;
+        let pulls = (1..=MAX_PULLS as u32 + 5)
+            .map(|number| ChangeRequest { title: "長".repeat(1000), ..open_pull_request(number) })
+            .collect::<Vec<ChangeRequest>>();
+
+        let payload = serde_json::to_vec(&GoogleChatApi::construct_digest(&Digest::new(pulls, now()), Locale::Ja)).unwrap();
+
+        assert!(payload.len() < limit, "{} bytes", payload.len());
+    }
+}
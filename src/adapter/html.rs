@@ -0,0 +1,88 @@
+//! Escaping for text interpolated into the HTML subset understood by Google
+//! Chat cards, Matrix `formatted_body` and HTML email.
+
+use crate::domain::model::{change_request::TextLine, locale::Label};
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+pub fn link(url: &str, label: &str) -> String {
+    format!("<a href=\"{}\">{}</a>", escape(url), escape(label))
+}
+
+/// Comma-separated reviewers with their state emoji, or the localized
+/// "no reviewers" label. Logins go through `escape`, so HTML bodies pass
+/// [`escape`] and their plain-text alternatives pass the login through as is.
+pub fn reviewers(text_line: &TextLine, escape: impl Fn(&str) -> String) -> String {
+    let reviewers = text_line
+        .reviewer_states()
+        .iter()
+        .map(|(reviewer, state)| format!("{} {}", state.emoji(), escape(&reviewer.login)))
+        .collect::<Vec<String>>();
+
+    if reviewers.is_empty() {
+        escape(text_line.locale.label(Label::NoReviewers))
+    } else {
+        reviewers.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod html_test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::domain::model::change_request::{
+        ChangeRequest,
+        ReviewVerdict,
+        fixture::{open_pull_request, reviewer},
+    };
+
+    #[rstest]
+    #[case("Fix login", "Fix login")]
+    #[case("<script>alert('x')</script>", "&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt;")]
+    #[case("Tom & \"Jerry\"", "Tom &amp; &quot;Jerry&quot;")]
+    #[case("日本語のタイトル", "日本語のタイトル")]
+    fn test_escape(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(expected, escape(text));
+    }
+
+    #[rstest]
+    #[case("https://github.com/reo0306/gospo/pull/1", "Test", "<a href=\"https://github.com/reo0306/gospo/pull/1\">Test</a>")]
+    #[case("https://example.com/?a=1&b=\"2\"", "<b>", "<a href=\"https://example.com/?a=1&amp;b=&quot;2&quot;\">&lt;b&gt;</a>")]
+    fn test_link(#[case] url: &str, #[case] label: &str, #[case] expected: &str) {
+        assert_eq!(expected, link(url, label));
+    }
+
+    #[test]
+    fn test_reviewers() {
+        let pull = ChangeRequest {
+            reviewers: vec![reviewer("<b>", ReviewVerdict::Approved), reviewer("test2", ReviewVerdict::Pending)],
+            ..open_pull_request(1)
+        };
+
+        assert_eq!("✅ &lt;b&gt;, ⏳ test2", reviewers(&pull.text_line(), escape));
+        assert_eq!("✅ <b>, ⏳ test2", reviewers(&pull.text_line(), |login| login.to_string()));
+    }
+
+    #[test]
+    fn test_reviewers_empty() {
+        let pull = ChangeRequest { reviewers: Vec::new(), ..open_pull_request(1) };
+
+        assert_eq!("none", reviewers(&pull.text_line(), escape));
+    }
+}
@@ -0,0 +1,286 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+use crate::adapter::{
+    error::Error,
+    notifier::{self, Notifier, Payload, Rendered},
+};
+use crate::domain::text;
+use crate::domain::model::{
+    change_request::TextLine,
+    digest::Digest,
+    line::{PushMessage, Message, Carousel, Bubble, FlexBox, Component, Text},
+    locale::{Label, Locale},
+};
+
+pub const LINE_API_URI: &str = "https://api.line.me";
+// A carousel holds at most 12 bubbles; the first one is the summary.
+const MAX_PULLS: usize = 11;
+const MAX_ALT_TEXT_CHARS: usize = 400;
+const SUBTLE_COLOR: &str = "#999999";
+
+pub struct LineApi;
+
+impl LineApi {
+    pub fn construct_digest(to: &str, digest: &Digest, locale: Locale) -> PushMessage {
+        let now = digest.generated_at;
+        let text_lines = digest.pulls
+            .iter()
+            .map(|pull| pull.text_line().locale(locale))
+            .collect::<Vec<TextLine>>();
+
+        let mut bubbles = vec![Self::summary_bubble(text_lines.len(), locale)];
+        bubbles.extend(text_lines.iter().take(MAX_PULLS).map(|text_line| Self::pull_request_bubble(text_line, now)));
+
+        PushMessage {
+            to: to.to_string(),
+            messages: vec![
+                Message::Flex {
+                    alt_text: text::truncate(&digest.text(locale), MAX_ALT_TEXT_CHARS),
+                    contents: Carousel { contents: bubbles },
+                }
+            ],
+        }
+    }
+
+    fn summary_bubble(count: usize, locale: Locale) -> Bubble {
+        let mut contents: Vec<Component> = vec![
+            Text::new(locale.label(Label::OpenPullRequest)).bold().size("lg").into(),
+            Text::new(&locale.open_pull_requests(count)).size("sm").color(SUBTLE_COLOR).into(),
+        ];
+
+        if count > MAX_PULLS {
+            contents.push(Text::new(&locale.more(count - MAX_PULLS)).size("sm").color(SUBTLE_COLOR).into());
+        }
+
+        Bubble { body: FlexBox::vertical(contents), footer: None }
+    }
+
+    fn pull_request_bubble(text_line: &TextLine, now: NaiveDateTime) -> Bubble {
+        let locale = text_line.locale;
+        let pull = text_line.pull;
+
+        let reviewers = text_line
+            .reviewer_states()
+            .iter()
+            .map(|(reviewer, state)| format!("{} {}", state.emoji(), reviewer.login))
+            .collect::<Vec<String>>();
+
+        Bubble {
+            body: FlexBox::vertical(vec![
+                Text::new(&pull.short_title()).bold().into(),
+                Text::new(&format!("{}#{}", text_line.repo.full_name, pull.number)).size("sm").color(SUBTLE_COLOR).into(),
+                Component::Separator,
+                Self::fact(locale.label(Label::Status), &pull.state),
+                Self::fact(
+                    locale.label(Label::Reviewers),
+                    &if reviewers.is_empty() { locale.label(Label::NoReviewers).to_string() } else { reviewers.join("\n") }
+                ),
+                Text::new(&format!(
+                    "{} · {}",
+                    locale.created_by(&pull.user.login, &text_line.created_at()),
+                    locale.relative_age(&text_line.age(now))
+                )).size("xs").color(SUBTLE_COLOR).into(),
+            ]),
+            footer: Some(FlexBox::vertical(vec![Component::link_button(locale.label(Label::OpenButton), &pull.html_url)])),
+        }
+    }
+
+    fn fact(title: &str, value: &str) -> Component {
+        FlexBox::baseline(vec![
+            Text::new(title).size("sm").color(SUBTLE_COLOR).flex(2).into(),
+            Text::new(value).size("sm").flex(5).into(),
+        ]).into()
+    }
+
+    pub async fn push(api_uri: &str, channel_access_token: &str, payload: &PushMessage) -> Result<(), Error> {
+        let url = format!("{}/v2/bot/message/push", api_uri.trim_end_matches('/'));
+
+        let request = reqwest::Client::new()
+            .post(&url)
+            .bearer_auth(channel_access_token)
+            .json(payload);
+
+        notifier::send(&url, request).await?;
+
+        Ok(())
+    }
+}
+
+pub struct LineNotifier {
+    name: String,
+    to: String,
+    api_uri: String,
+    channel_access_token: String,
+    locale: Locale,
+}
+
+impl LineNotifier {
+    /// `to` is the user, group or room id the digest is pushed to.
+    pub fn new(name: &str, to: &str, channel_access_token: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            to: to.to_string(),
+            api_uri: LINE_API_URI.to_string(),
+            channel_access_token: channel_access_token.to_string(),
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn api_uri(mut self, api_uri: &str) -> Self {
+        self.api_uri = api_uri.to_string();
+        self
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+#[async_trait]
+impl Notifier for LineNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, digest: &Digest) -> anyhow::Result<Rendered> {
+        let push = LineApi::construct_digest(&self.to, digest, self.locale);
+
+        Ok(Rendered {
+            text: digest.text(self.locale),
+            payload: Payload::Line(push),
+        })
+    }
+
+    async fn send(&self, rendered: &Rendered) -> anyhow::Result<()> {
+        let Payload::Line(push) = &rendered.payload else {
+            return Err(notifier::unexpected_payload(&self.name));
+        };
+
+        Ok(LineApi::push(&self.api_uri, &self.channel_access_token, push).await?)
+    }
+}
+
+#[cfg(test)]
+mod line_test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::domain::model::change_request::ChangeRequest;
+    use crate::domain::model::change_request::fixture::{now, open_pull_request};
+
+    #[test]
+    fn test_construct_digest() {
+        let push = LineApi::construct_digest("C123", &Digest::new(vec![open_pull_request(1)], now()), Locale::Ja);
+
+        assert_eq!(
+            serde_json::json!({
+                "to": "C123",
+                "messages": [{
+                    "type": "flex",
+                    "altText": "オープン中のプルリクエスト 1 件\nTest - reo0306/gospo#1 https://github.com/reo0306/gospo/pull/1",
+                    "contents": {
+                        "type": "carousel",
+                        "contents": [
+                            {
+                                "type": "bubble",
+                                "body": {
+                                    "type": "box",
+                                    "layout": "vertical",
+                                    "spacing": "sm",
+                                    "contents": [
+                                        { "type": "text", "text": "オープン中のプルリクエスト", "wrap": true, "weight": "bold", "size": "lg" },
+                                        { "type": "text", "text": "オープン中のプルリクエスト 1 件", "wrap": true, "size": "sm", "color": "#999999" },
+                                    ]
+                                }
+                            },
+                            {
+                                "type": "bubble",
+                                "body": {
+                                    "type": "box",
+                                    "layout": "vertical",
+                                    "spacing": "sm",
+                                    "contents": [
+                                        { "type": "text", "text": "Test", "wrap": true, "weight": "bold" },
+                                        { "type": "text", "text": "reo0306/gospo#1", "wrap": true, "size": "sm", "color": "#999999" },
+                                        { "type": "separator" },
+                                        {
+                                            "type": "box",
+                                            "layout": "baseline",
+                                            "spacing": "sm",
+                                            "contents": [
+                                                { "type": "text", "text": "ステータス", "wrap": true, "size": "sm", "color": "#999999", "flex": 2 },
+                                                { "type": "text", "text": "open", "wrap": true, "size": "sm", "flex": 5 },
+                                            ]
+                                        },
+                                        {
+                                            "type": "box",
+                                            "layout": "baseline",
+                                            "spacing": "sm",
+                                            "contents": [
+                                                { "type": "text", "text": "レビュアー", "wrap": true, "size": "sm", "color": "#999999", "flex": 2 },
+                                                { "type": "text", "text": "✅ test\n⏳ test2", "wrap": true, "size": "sm", "flex": 5 },
+                                            ]
+                                        },
+                                        { "type": "text", "text": "reo0306 が 2024年07月16日 20:09 に作成 · 2日前", "wrap": true, "size": "xs", "color": "#999999" },
+                                    ]
+                                },
+                                "footer": {
+                                    "type": "box",
+                                    "layout": "vertical",
+                                    "spacing": "sm",
+                                    "contents": [
+                                        {
+                                            "type": "button",
+                                            "style": "link",
+                                            "action": { "type": "uri", "label": "PRを開く", "uri": "https://github.com/reo0306/gospo/pull/1" }
+                                        }
+                                    ]
+                                }
+                            },
+                        ]
+                    }
+                }]
+            }),
+            serde_json::json!(push)
+        );
+    }
+
+    #[test]
+    fn test_construct_digest_bubble_limit() {
+        let pulls = (1..=15).map(open_pull_request).collect::<Vec<ChangeRequest>>();
+
+        let push = LineApi::construct_digest("C123", &Digest::new(pulls, now()), Locale::En);
+        let Message::Flex { contents, .. } = &push.messages[0];
+
+        assert_eq!(12, contents.contents.len());
+        assert_eq!(
+            Component::from(Text::new("and 4 more").size("sm").color(SUBTLE_COLOR)),
+            contents.contents[0].body.contents[2]
+        );
+    }
+
+    #[rstest]
+    #[case(200, true)]
+    #[case(400, false)]
+    #[tokio::test]
+    async fn test_line_notifier_notify(#[case] status: usize, #[case] ok: bool) {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v2/bot/message/push")
+            .match_header("authorization", "Bearer channel-token")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "to": "C123" })))
+            .with_status(status)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let notifier = LineNotifier::new("line", "C123", "channel-token").api_uri(&server.url());
+        let report = notifier.notify(&Digest::new(vec![open_pull_request(1)], now())).await;
+
+        assert_eq!(ok, report.result.is_ok());
+
+        mock.assert_async().await;
+    }
+}
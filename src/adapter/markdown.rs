@@ -0,0 +1,68 @@
+//! Escaping for text interpolated into the CommonMark-ish markup used by
+//! Teams, Discord and Mattermost.
+//!
+//! Backslash-escaping the punctuation that starts emphasis, links, code or
+//! headings keeps pull request titles and logins rendered as plain text.
+
+const SPECIAL: &[char] = &['\\', '`', '*', '_', '~', '[', ']', '(', ')', '#', '|', '<', '>'];
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if SPECIAL.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Percent-encodes the characters that would end a `(url)` link target early.
+pub fn escape_url(url: &str) -> String {
+    let mut escaped = String::with_capacity(url.len());
+
+    for c in url.chars() {
+        match c {
+            '(' => escaped.push_str("%28"),
+            ')' => escaped.push_str("%29"),
+            ' ' => escaped.push_str("%20"),
+            '<' => escaped.push_str("%3C"),
+            '>' => escaped.push_str("%3E"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+pub fn link(url: &str, label: &str) -> String {
+    format!("[{}]({})", escape(label), escape_url(url))
+}
+
+#[cfg(test)]
+mod markdown_test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("Fix login", "Fix login")]
+    #[case("**bold** _it_", "\\*\\*bold\\*\\* \\_it\\_")]
+    #[case("[docs](https://evil.example)", "\\[docs\\]\\(https://evil.example\\)")]
+    #[case("# heading", "\\# heading")]
+    #[case("a\\b", "a\\\\b")]
+    #[case("日本語のタイトル", "日本語のタイトル")]
+    fn test_escape(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(expected, escape(text));
+    }
+
+    #[rstest]
+    #[case("https://github.com/reo0306/gospo/pull/1", "Test", "[Test](https://github.com/reo0306/gospo/pull/1)")]
+    #[case("https://github.com/reo0306/gospo/pull/1", "a]b", "[a\\]b](https://github.com/reo0306/gospo/pull/1)")]
+    #[case("https://example.com/a)b c", "x", "[x](https://example.com/a%29b%20c)")]
+    fn test_link(#[case] url: &str, #[case] label: &str, #[case] expected: &str) {
+        assert_eq!(expected, link(url, label));
+    }
+}
@@ -0,0 +1,308 @@
+use std::{fs, path::Path};
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+
+use crate::adapter::{
+    error::{self, Error},
+    html,
+    notifier::{self, Notifier, Payload, Rendered},
+};
+use crate::domain::model::{
+    change_request::{ChangeRequest, TextLine},
+    digest::Digest,
+    locale::{Label, Locale},
+    matrix::{RoomMessage, SendResponse},
+};
+
+
+pub struct MatrixApi;
+
+impl MatrixApi {
+    pub fn construct_digest(pulls: &[ChangeRequest], locale: Locale, now: NaiveDateTime) -> RoomMessage {
+        let text_lines = pulls
+            .iter()
+            .map(|pull| pull.text_line().locale(locale))
+            .collect::<Vec<TextLine>>();
+
+        let count = locale.open_pull_requests(text_lines.len());
+
+        let mut body = vec![locale.label(Label::OpenPullRequest).to_string()];
+        body.extend(text_lines.iter().map(|text_line| Self::pull_request_text(text_line, now)));
+        body.push(count.to_string());
+
+        let items = text_lines
+            .iter()
+            .map(|text_line| Self::pull_request_html(text_line, now))
+            .collect::<String>();
+
+        let formatted_body = if items.is_empty() {
+            format!("<h4>{}</h4><p>{}</p>", html::escape(locale.label(Label::OpenPullRequest)), html::escape(&count))
+        } else {
+            format!("<h4>{}</h4><ul>{}</ul><p>{}</p>", html::escape(locale.label(Label::OpenPullRequest)), items, html::escape(&count))
+        };
+
+        RoomMessage::new(&body.join("\n"), &formatted_body)
+    }
+
+    fn pull_request_text(text_line: &TextLine, now: NaiveDateTime) -> String {
+        let locale = text_line.locale;
+        let pull = text_line.pull;
+
+        format!(
+            "- {} ({}#{}) {} · {} · {}",
+            pull.short_title(),
+            text_line.repo.full_name,
+            pull.number,
+            pull.html_url,
+            html::reviewers(text_line, |login| login.to_string()),
+            locale.relative_age(&text_line.age(now))
+        )
+    }
+
+    fn pull_request_html(text_line: &TextLine, now: NaiveDateTime) -> String {
+        let locale = text_line.locale;
+        let pull = text_line.pull;
+
+        format!(
+            "<li><b>{}</b> <code>{}#{}</code><br>{}: {} · {}: {}<br>{} · {}</li>",
+            html::link(&pull.html_url, &pull.short_title()),
+            html::escape(&text_line.repo.full_name),
+            pull.number,
+            html::escape(locale.label(Label::Status)),
+            html::escape(&pull.state),
+            html::escape(locale.label(Label::Reviewers)),
+            html::reviewers(text_line, html::escape),
+            locale.created_by(&html::link(&pull.user.html_url, &pull.user.login), &html::escape(&text_line.created_at())),
+            html::escape(&locale.relative_age(&text_line.age(now)))
+        )
+    }
+
+    /// Percent-encodes a room id such as `!abc:example.org` for use in a path.
+    fn encode_path_segment(segment: &str) -> String {
+        segment
+            .bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+                _ => format!("%{:02X}", byte),
+            })
+            .collect()
+    }
+
+    pub async fn send(homeserver: &str, room_id: &str, access_token: &str, message: &RoomMessage) -> Result<String, Error> {
+        let client = reqwest::Client::new();
+
+        let url = format!(
+            "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
+            homeserver.trim_end_matches('/'),
+            Self::encode_path_segment(room_id),
+            Self::transaction_id()
+        );
+
+        let request = client
+            .put(&url)
+            .bearer_auth(access_token)
+            .json(message);
+
+        let response = notifier::send(&url, request).await?;
+
+        Ok(error::json::<SendResponse>(&url, response).await?.event_id)
+    }
+
+    fn transaction_id() -> String {
+        format!("pull-request-digest-{}", Utc::now().timestamp_nanos_opt().unwrap_or_default())
+    }
+}
+
+pub struct MatrixNotifier {
+    name: String,
+    homeserver: String,
+    room_id: String,
+    access_token: String,
+    locale: Locale,
+    state_file: Option<String>,
+}
+
+impl MatrixNotifier {
+    pub fn new(name: &str, homeserver: &str, room_id: &str, access_token: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            homeserver: homeserver.to_string(),
+            room_id: room_id.to_string(),
+            access_token: access_token.to_string(),
+            locale: Locale::default(),
+            state_file: None,
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Remembers the digest's event id in `path` so later runs edit it via `m.replace`.
+    pub fn state_file(mut self, path: &str) -> Self {
+        self.state_file = Some(path.to_string());
+        self
+    }
+
+    fn previous_event_id(&self) -> Option<String> {
+        let path = self.state_file.as_ref()?;
+        let event_id = fs::read_to_string(path).ok()?;
+
+        Some(event_id.trim().to_string()).filter(|event_id| !event_id.is_empty())
+    }
+}
+
+#[async_trait]
+impl Notifier for MatrixNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, digest: &Digest) -> anyhow::Result<Rendered> {
+        let message = MatrixApi::construct_digest(&digest.pulls, self.locale, digest.generated_at);
+
+        Ok(Rendered {
+            text: message.body.clone(),
+            payload: Payload::Matrix(message),
+        })
+    }
+
+    async fn send(&self, rendered: &Rendered) -> anyhow::Result<()> {
+        let Payload::Matrix(message) = &rendered.payload else {
+            return Err(notifier::unexpected_payload(&self.name));
+        };
+
+        match self.previous_event_id() {
+            Some(event_id) => {
+                MatrixApi::send(&self.homeserver, &self.room_id, &self.access_token, &message.clone().replacing(&event_id)).await?;
+            },
+            None => {
+                let event_id = MatrixApi::send(&self.homeserver, &self.room_id, &self.access_token, message).await?;
+
+                if let Some(path) = &self.state_file {
+                    fs::write(Path::new(path), event_id)
+                        .with_context(|| format!("failed to write Matrix state file {}", path))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod matrix_test {
+    use mockito::Matcher;
+
+    use super::*;
+    use crate::domain::model::change_request::fixture::{now, open_pull_request, open_pull_request_titled};
+
+    fn state_file(name: &str) -> String {
+        let path = std::env::temp_dir().join(name);
+        let _ = fs::remove_file(&path);
+
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_construct_digest() {
+        let message = MatrixApi::construct_digest(&[open_pull_request_titled("a <b>")], Locale::En, now());
+
+        assert_eq!(
+            "Open Pull Request\n- a <b> (reo0306/gospo#1) https://github.com/reo0306/gospo/pull/1 · ✅ test, ⏳ test2 · 2 days ago\n1 open pull request",
+            message.body
+        );
+        assert_eq!(
+            "<h4>Open Pull Request</h4><ul>\
+            <li><b><a href=\"https://github.com/reo0306/gospo/pull/1\">a &lt;b&gt;</a></b> <code>reo0306/gospo#1</code><br>\
+            Status: open · Reviewers: ✅ test, ⏳ test2<br>\
+            Created by <a href=\"https://github.com/reo0306\">reo0306</a> on 2024-07-16 20:09:31 · 2 days ago</li>\
+            </ul><p>1 open pull request</p>",
+            message.formatted_body
+        );
+        assert_eq!("org.matrix.custom.html", message.format);
+    }
+
+    #[test]
+    fn test_replacing() {
+        let message = RoomMessage::new("digest", "<b>digest</b>").replacing("$event");
+
+        assert_eq!(
+            serde_json::json!({
+                "msgtype": "m.notice",
+                "body": "* digest",
+                "format": "org.matrix.custom.html",
+                "formatted_body": "* <b>digest</b>",
+                "m.new_content": {
+                    "msgtype": "m.notice",
+                    "body": "digest",
+                    "format": "org.matrix.custom.html",
+                    "formatted_body": "<b>digest</b>",
+                },
+                "m.relates_to": { "rel_type": "m.replace", "event_id": "$event" },
+            }),
+            serde_json::json!(message)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_matrix_notifier_sends_then_edits() {
+        let mut server = mockito::Server::new_async().await;
+        let path = state_file("pullrequest-slack-bot-matrix-test.state");
+
+        let send = server
+            .mock("PUT", Matcher::Regex(r"^/_matrix/client/v3/rooms/%21room%3Aexample\.org/send/m\.room\.message/.+$".to_string()))
+            .match_header("authorization", "Bearer token")
+            .match_body(Matcher::PartialJson(serde_json::json!({ "msgtype": "m.notice" })))
+            .with_status(200)
+            .with_body(r#"{"event_id": "$first"}"#)
+            .create_async()
+            .await;
+
+        let notifier = MatrixNotifier::new("matrix", &server.url(), "!room:example.org", "token").state_file(&path);
+        let digest = Digest::new(vec![open_pull_request(1)], now());
+
+        assert!(notifier.notify(&digest).await.result.is_ok());
+        assert_eq!("$first", fs::read_to_string(&path).unwrap());
+
+        send.assert_async().await;
+        send.remove_async().await;
+
+        let edit = server
+            .mock("PUT", Matcher::Regex(r"^/_matrix/client/v3/rooms/.+/send/m\.room\.message/.+$".to_string()))
+            .match_body(Matcher::PartialJson(serde_json::json!({
+                "m.relates_to": { "rel_type": "m.replace", "event_id": "$first" }
+            })))
+            .with_status(200)
+            .with_body(r#"{"event_id": "$edit"}"#)
+            .create_async()
+            .await;
+
+        assert!(notifier.notify(&digest).await.result.is_ok());
+        assert_eq!("$first", fs::read_to_string(&path).unwrap());
+
+        edit.assert_async().await;
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_matrix_notifier_rejected() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PUT", Matcher::Any)
+            .with_status(403)
+            .with_body(r#"{"errcode": "M_FORBIDDEN", "error": "not in room"}"#)
+            .create_async()
+            .await;
+
+        let notifier = MatrixNotifier::new("matrix", &server.url(), "!room:example.org", "token");
+        let report = notifier.notify(&Digest::new(Vec::new(), now())).await;
+
+        assert!(report.result.is_err());
+
+        mock.assert_async().await;
+    }
+}
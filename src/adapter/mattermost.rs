@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+use crate::adapter::{
+    markdown,
+    notifier::{self, Notifier, Payload, Rendered},
+};
+use crate::domain::model::{
+    change_request::{ChangeRequest, ReviewStatus, ReviewVerdict, TextLine},
+    digest::Digest,
+    locale::{Label, Locale},
+    mattermost::{Mattermost, Attachment, Field},
+};
+
+// Mattermost truncates posts above 16383 characters; attachments count towards it.
+const MAX_PULLS: usize = 30;
+
+const COLOR_APPROVED: &str = "#2eb886";
+const COLOR_CHANGES_REQUESTED: &str = "#d00000";
+const COLOR_PENDING: &str = "#daa038";
+
+pub struct MattermostApi;
+
+impl MattermostApi {
+    /// `users` maps GitHub logins to chat usernames; mapped reviewers are `@`-mentioned.
+    pub fn construct_digest(
+        pulls: &[ChangeRequest],
+        locale: Locale,
+        users: &HashMap<String, String>,
+        now: NaiveDateTime,
+    ) -> Mattermost {
+        let text_lines = pulls
+            .iter()
+            .map(|pull| pull.text_line().locale(locale))
+            .collect::<Vec<TextLine>>();
+
+        let mut text = vec![
+            format!("#### {}", locale.label(Label::OpenPullRequest)),
+            locale.open_pull_requests(text_lines.len()),
+        ];
+
+        if text_lines.len() > MAX_PULLS {
+            text.push(locale.more(text_lines.len() - MAX_PULLS));
+        }
+
+        Mattermost {
+            text: text.join("\n"),
+            attachments: text_lines
+                .iter()
+                .take(MAX_PULLS)
+                .map(|text_line| Self::pull_request_attachment(text_line, users, now))
+                .collect(),
+        }
+    }
+
+    fn pull_request_attachment(text_line: &TextLine, users: &HashMap<String, String>, now: NaiveDateTime) -> Attachment {
+        let locale = text_line.locale;
+        let pull = text_line.pull;
+        let states = text_line.reviewer_states();
+
+        let reviewers = states
+            .iter()
+            .map(|(reviewer, state)| format!("{} {}", state.shortcode(), Self::reviewer(&reviewer.login, *state, users)))
+            .collect::<Vec<String>>();
+
+        let title = pull.short_title();
+
+        Attachment {
+            fallback: format!("{} - {}#{}", title, text_line.repo.full_name, pull.number),
+            color: Self::color(pull.status()).to_string(),
+            title: Self::escape(&title),
+            title_link: pull.html_url.to_string(),
+            text: format!(
+                "`{}#{}`\n{} · {}",
+                text_line.repo.full_name.replace('`', "'"),
+                pull.number,
+                locale.created_by(&markdown::link(&pull.user.html_url, &Self::escape(&pull.user.login)), &text_line.created_at()),
+                locale.relative_age(&text_line.age(now))
+            ),
+            fields: vec![
+                Field::new(locale.label(Label::Status), &Self::escape(&pull.state)),
+                Field::new(
+                    locale.label(Label::Reviewers),
+                    &if reviewers.is_empty() { locale.label(Label::NoReviewers).to_string() } else { reviewers.join("\n") }
+                ),
+            ],
+        }
+    }
+
+    /// Only reviewers still owing a review are pinged.
+    fn reviewer(login: &str, state: ReviewVerdict, users: &HashMap<String, String>) -> String {
+        match users.get(login) {
+            Some(username) if state != ReviewVerdict::Approved => format!("@{}", username.trim_start_matches('@')),
+            _ => Self::escape(login),
+        }
+    }
+
+    /// Escapes markdown and defuses `@channel`, `@all` or `@user` in PR data.
+    fn escape(text: &str) -> String {
+        markdown::escape(text).replace('@', "@\u{200B}")
+    }
+
+    fn color(status: ReviewStatus) -> &'static str {
+        match status {
+            ReviewStatus::ChangesRequested => COLOR_CHANGES_REQUESTED,
+            ReviewStatus::Approved => COLOR_APPROVED,
+            ReviewStatus::Unassigned | ReviewStatus::AwaitingReview => COLOR_PENDING,
+        }
+    }
+}
+
+pub struct MattermostNotifier {
+    name: String,
+    webhook_uri: String,
+    locale: Locale,
+    users: HashMap<String, String>,
+}
+
+impl MattermostNotifier {
+    pub fn new(name: &str, webhook_uri: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            webhook_uri: webhook_uri.to_string(),
+            locale: Locale::default(),
+            users: HashMap::new(),
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn users(mut self, users: HashMap<String, String>) -> Self {
+        self.users = users;
+        self
+    }
+}
+
+#[async_trait]
+impl Notifier for MattermostNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, digest: &Digest) -> anyhow::Result<Rendered> {
+        let mattermost = MattermostApi::construct_digest(&digest.pulls, self.locale, &self.users, digest.generated_at);
+
+        let mut text = vec![mattermost.text.clone()];
+        text.extend(mattermost.attachments.iter().map(|attachment| attachment.fallback.to_string()));
+
+        Ok(Rendered {
+            text: text.join("\n"),
+            payload: Payload::Mattermost(mattermost),
+        })
+    }
+
+    async fn send(&self, rendered: &Rendered) -> anyhow::Result<()> {
+        let Payload::Mattermost(mattermost) = &rendered.payload else {
+            return Err(notifier::unexpected_payload(&self.name));
+        };
+
+        Ok(notifier::post_json("Mattermost webhook", &self.webhook_uri, mattermost).await?)
+    }
+}
+
+#[cfg(test)]
+mod mattermost_test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::domain::model::change_request::fixture::{now, open_pull_request, open_pull_request_titled};
+
+    #[test]
+    fn test_construct_digest() {
+        let users = HashMap::from([
+            ("test".to_string(), "alice".to_string()),
+            ("test2".to_string(), "bob".to_string()),
+        ]);
+
+        let mattermost = MattermostApi::construct_digest(&[open_pull_request(1)], Locale::En, &users, now());
+
+        assert_eq!(
+            serde_json::json!({
+                "text": "#### Open Pull Request\n1 open pull request",
+                "attachments": [{
+                    "fallback": "Test - reo0306/gospo#1",
+                    "color": COLOR_PENDING,
+                    "title": "Test",
+                    "title_link": "https://github.com/reo0306/gospo/pull/1",
+                    "text": "`reo0306/gospo#1`\nCreated by [reo0306](https://github.com/reo0306) on 2024-07-16 20:09:31 · 2 days ago",
+                    "fields": [
+                        { "title": "Status", "value": "open", "short": true },
+                        { "title": "Reviewers", "value": ":white_check_mark: test\n:hourglass_flowing_sand: @bob", "short": true },
+                    ]
+                }]
+            }),
+            serde_json::json!(mattermost)
+        );
+    }
+
+    #[rstest]
+    #[case("@channel please review", "@\u{200B}channel please review")]
+    #[case("[x](https://evil.example)", "\\[x\\]\\(https://evil.example\\)")]
+    fn test_construct_digest_escapes_untrusted_data(#[case] title: &str, #[case] expected: &str) {
+        let mattermost = MattermostApi::construct_digest(&[open_pull_request_titled(title)], Locale::En, &HashMap::new(), now());
+
+        assert_eq!(expected, mattermost.attachments[0].title);
+    }
+
+    #[test]
+    fn test_construct_digest_without_user_mapping() {
+        let mattermost = MattermostApi::construct_digest(&[open_pull_request(1)], Locale::Ja, &HashMap::new(), now());
+
+        assert_eq!(
+            Field::new("レビュアー", ":white_check_mark: test\n:hourglass_flowing_sand: test2"),
+            mattermost.attachments[0].fields[1]
+        );
+    }
+
+    #[rstest]
+    #[case(200, "ok", true)]
+    #[case(400, "Unable to parse incoming data", false)]
+    #[tokio::test]
+    async fn test_mattermost_notifier_notify(#[case] status: usize, #[case] body: &str, #[case] ok: bool) {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hooks/xxx")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({ "text": "#### Open Pull Request\n1 open pull request" })))
+            .with_status(status)
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let notifier = MattermostNotifier::new("mattermost", &format!("{}/hooks/xxx", server.url()));
+        let report = notifier.notify(&Digest::new(vec![open_pull_request(1)], now())).await;
+
+        assert_eq!(ok, report.result.is_ok());
+
+        mock.assert_async().await;
+    }
+}
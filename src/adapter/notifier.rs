@@ -0,0 +1,252 @@
+use std::time::Duration;
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::{RequestBuilder, Response};
+use serde::Serialize;
+
+use crate::adapter::{
+    chatwork::ChatworkMessage,
+    email::Envelope,
+    error::{self, Error},
+};
+use crate::domain::model::{
+    digest::Digest,
+    discord::Discord,
+    google_chat::GoogleChat,
+    line::PushMessage,
+    matrix::RoomMessage,
+    mattermost::Mattermost,
+    slack::Slack,
+    teams::Teams,
+    webhook::DigestDocument,
+};
+
+pub const MAX_RETRIES: usize = 3;
+const DEFAULT_RETRY_AFTER_SECS: u64 = 1;
+const MAX_RETRY_AFTER_SECS: u64 = 60;
+
+pub struct Rendered {
+    pub payload: Payload,
+    pub text: String,
+}
+
+/// What a notifier rendered, kept typed until it is serialized for the wire.
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub enum Payload {
+    Slack(Slack),
+    Teams(Teams),
+    /// Webhook messages, posted in order.
+    Discord(Vec<Discord>),
+    GoogleChat(GoogleChat),
+    Mattermost(Mattermost),
+    Email(Envelope),
+    Matrix(RoomMessage),
+    Chatwork(ChatworkMessage),
+    Line(PushMessage),
+    Webhook(DigestDocument),
+}
+
+pub struct Report {
+    pub notifier: String,
+    pub result: Result<()>,
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn render(&self, digest: &Digest) -> Result<Rendered>;
+
+    async fn send(&self, rendered: &Rendered) -> Result<()>;
+
+    fn preview_url(&self, _rendered: &Rendered) -> Option<String> {
+        None
+    }
+
+    /// What `doctor` reports once the notifier is built; notifiers that can
+    /// probe their endpoint without posting override it.
+    async fn check(&self) -> Result<String> {
+        Ok("configured".to_string())
+    }
+
+    async fn notify(&self, digest: &Digest) -> Report {
+        let result = match self.render(digest) {
+            Ok(rendered) => self.send(&rendered).await,
+            Err(e) => Err(e),
+        };
+
+        Report { notifier: self.name().to_string(), result }
+    }
+}
+
+pub async fn notify_all(notifiers: &[Box<dyn Notifier>], digest: &Digest) -> Vec<Report> {
+    futures::future::join_all(notifiers.iter().map(|notifier| notifier.notify(digest))).await
+}
+
+/// Sends `request`, failing with the classified error on any non-2xx
+/// response. `target` names the endpoint in errors instead of its URL, since
+/// a webhook URL is itself the secret.
+pub async fn send(target: &str, request: RequestBuilder) -> Result<Response, Error> {
+    let response = request.send().await.map_err(|source| source.without_url());
+
+    error::check(target, response).await
+}
+
+/// `send`, waiting out up to `MAX_RETRIES` 429s for as long as the
+/// endpoint's `Retry-After` asks. Only requests whose body can be cloned are
+/// retried.
+pub async fn send_with_retry(target: &str, request: RequestBuilder) -> Result<Response, Error> {
+    let mut retries = 0;
+
+    loop {
+        let Some(retry) = request.try_clone() else {
+            return send(target, request).await;
+        };
+
+        match send(target, retry).await {
+            Err(Error::RateLimited { retry_after, .. }) if retries < MAX_RETRIES => {
+                let wait = retry_after.unwrap_or(DEFAULT_RETRY_AFTER_SECS).min(MAX_RETRY_AFTER_SECS);
+
+                tokio::time::sleep(Duration::from_secs(wait)).await;
+                retries += 1;
+            },
+            result => return result,
+        }
+    }
+}
+
+/// A notifier was handed a payload some other notifier rendered.
+pub fn unexpected_payload(notifier: &str) -> anyhow::Error {
+    anyhow::anyhow!("{} cannot send a payload rendered by another notifier", notifier)
+}
+
+/// POSTs `payload` as JSON to a webhook.
+pub async fn post_json<T: Serialize + ?Sized>(target: &str, uri: &str, payload: &T) -> Result<(), Error> {
+    send(target, reqwest::Client::new().post(uri).json(payload)).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod notifier_test {
+    use anyhow::anyhow;
+    use chrono::NaiveDateTime;
+    use rstest::rstest;
+
+    use super::*;
+
+    struct FakeNotifier {
+        name: String,
+        fail_render: bool,
+        fail_send: bool,
+    }
+
+    impl FakeNotifier {
+        fn new(name: &str, fail_render: bool, fail_send: bool) -> Self {
+            Self { name: name.to_string(), fail_render, fail_send }
+        }
+    }
+
+    #[async_trait]
+    impl Notifier for FakeNotifier {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn render(&self, digest: &Digest) -> Result<Rendered> {
+            if self.fail_render {
+                return Err(anyhow!("render failed"));
+            }
+
+            Ok(Rendered {
+                payload: Payload::Webhook(DigestDocument::new(digest)),
+                text: format!("{} pull requests", digest.pulls.len()),
+            })
+        }
+
+        async fn send(&self, _rendered: &Rendered) -> Result<()> {
+            if self.fail_send {
+                Err(anyhow!("send failed"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notify_all_reports_each_notifier() {
+        let notifiers: Vec<Box<dyn Notifier>> = vec![
+            Box::new(FakeNotifier::new("ok", false, false)),
+            Box::new(FakeNotifier::new("send", false, true)),
+            Box::new(FakeNotifier::new("render", true, false)),
+        ];
+        let digest = Digest::new(
+            Vec::new(),
+            NaiveDateTime::parse_from_str("2024-07-19T08:09:31Z", "%Y-%m-%dT%H:%M:%SZ").unwrap(),
+        );
+
+        let reports = notify_all(&notifiers, &digest).await;
+
+        assert_eq!(
+            vec![("ok", None), ("send", Some("send failed".to_string())), ("render", Some("render failed".to_string()))],
+            reports
+                .iter()
+                .map(|report| (report.notifier.as_str(), report.result.as_ref().err().map(|e| e.to_string())))
+                .collect::<Vec<(&str, Option<String>)>>()
+        );
+    }
+
+    #[rstest]
+    #[case(200, "ok")]
+    #[case(204, "ok")]
+    #[case(401, "unauthorized")]
+    #[case(404, "not_found")]
+    #[case(429, "rate_limited")]
+    #[case(400, "status")]
+    #[case(500, "status")]
+    #[tokio::test]
+    async fn test_post_json(#[case] status: usize, #[case] expected: &str) {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hooks/secret-token")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::Json(serde_json::json!({ "text": "hi" })))
+            .with_status(status)
+            .with_body("invalid_payload")
+            .create_async()
+            .await;
+
+        let result = post_json("Chat webhook", &format!("{}/hooks/secret-token", server.url()), &serde_json::json!({ "text": "hi" })).await;
+
+        let kind = match &result {
+            Ok(()) => "ok",
+            Err(Error::Unauthorized { .. }) => "unauthorized",
+            Err(Error::NotFound { .. }) => "not_found",
+            Err(Error::RateLimited { .. }) => "rate_limited",
+            Err(Error::Status { body, .. }) => {
+                assert_eq!("invalid_payload", body);
+                "status"
+            },
+            Err(other) => panic!("unexpected {:?}", other),
+        };
+
+        assert_eq!(expected, kind);
+        if let Err(e) = result {
+            assert!(e.to_string().contains("Chat webhook"));
+            assert!(!e.to_string().contains("secret-token"));
+        }
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_send_hides_url_on_transport_error() {
+        let request = reqwest::Client::new().post("http://127.0.0.1:9/hooks/secret-token");
+
+        let error = send("Chat webhook", request).await.unwrap_err();
+
+        assert!(matches!(error, Error::Transport { .. }));
+        assert!(!format!("{:?}", error).contains("secret-token"));
+    }
+}
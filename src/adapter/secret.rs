@@ -0,0 +1,83 @@
+use std::{env, fs};
+
+use crate::adapter::error::Error;
+
+/// `name` from the environment (or `.env`), else the trimmed contents of the
+/// file `{name}_FILE` points at, as with Docker and Kubernetes secrets.
+pub fn var(name: &str) -> Result<String, Error> {
+    lookup(name, |key| env::var(key).ok())
+}
+
+/// `var`, reading variables through `env` rather than the process
+/// environment.
+pub fn lookup(name: &str, env: impl Fn(&str) -> Option<String>) -> Result<String, Error> {
+    let file = format!("{}_FILE", name);
+
+    match (env(name), env(&file)) {
+        (Some(value), _) if value.trim().is_empty() => Err(Error::Config(format!("{} is empty", name))),
+        (Some(value), _) => Ok(value),
+        (None, Some(path)) => {
+            let value = fs::read_to_string(&path)
+                .map_err(|e| Error::Config(format!("failed to read {} {}: {}", file, path, e)))?;
+
+            match value.trim() {
+                "" => Err(Error::Config(format!("{} {} is empty", file, path))),
+                value => Ok(value.to_string()),
+            }
+        },
+        (None, None) => Err(Error::Config(format!(
+            "{} is not set; export it, add it to .env, or point {} at a file holding it",
+            name, file
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod secret_test {
+    use std::{collections::HashMap, io::Write};
+
+    use super::*;
+
+    fn env_of<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        let vars = vars.iter().copied().collect::<HashMap<&str, &str>>();
+
+        move |key| vars.get(key).map(|value| value.to_string())
+    }
+
+    #[test]
+    fn it_reads_variable() {
+        assert_eq!("abc", lookup("SECRET", env_of(&[("SECRET", "abc")])).unwrap());
+    }
+
+    #[test]
+    fn it_reads_file() {
+        let path = env::temp_dir().join("secret_test_file");
+        writeln!(fs::File::create(&path).unwrap(), "from-file").unwrap();
+
+        assert_eq!("from-file", lookup("SECRET", env_of(&[("SECRET_FILE", &path.to_string_lossy())])).unwrap());
+    }
+
+    #[test]
+    fn it_prefers_variable_over_file() {
+        assert_eq!("abc", lookup("SECRET", env_of(&[("SECRET", "abc"), ("SECRET_FILE", "/nonexistent/secret")])).unwrap());
+    }
+
+    #[test]
+    fn it_explains_missing_variable() {
+        let error = lookup("SECRET", env_of(&[])).unwrap_err();
+
+        assert!(matches!(error, Error::Config(_)));
+        assert_eq!(
+            "SECRET is not set; export it, add it to .env, or point SECRET_FILE at a file holding it",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn it_rejects_empty_and_unreadable() {
+        let env = env_of(&[("EMPTY", " "), ("UNREADABLE_FILE", "/nonexistent/secret")]);
+
+        assert_eq!("EMPTY is empty", lookup("EMPTY", &env).unwrap_err().to_string());
+        assert!(lookup("UNREADABLE", &env).unwrap_err().to_string().starts_with("failed to read UNREADABLE_FILE /nonexistent/secret"));
+    }
+}
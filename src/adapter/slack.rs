@@ -1,44 +1,279 @@
-use std::env;
+pub mod mrkdwn;
+pub mod template;
 
-use crate::domain::model::slack::{Slack, Message, Text};
+use std::fmt;
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::adapter::{
+    error::Error,
+    notifier::{self, Notifier, Payload, Rendered},
+    slack::template::{DigestView, Templates},
+};
+use crate::domain::text;
+use crate::domain::model::{
+    change_request::{ChangeRequest, TextLine},
+    digest::Digest,
+    locale::{Label, Locale},
+    slack::{Slack, Block, Header, Section, Context, Element, Text},
+};
+
+const WEBHOOK: &str = "Slack webhook";
+const BLOCK_KIT_BUILDER_URI: &str = "https://app.slack.com/block-kit-builder/";
+// Slack rejects messages with more than 50 blocks.
+const MAX_BLOCKS: usize = 50;
+const BLOCKS_PER_PULL: usize = 3;
+// Slack's cap on a section's or a context element's text.
+const MAX_SECTION_CHARS: usize = 3000;
+
+/// Context on a digest the Slack webhook did not take.
+#[derive(Debug)]
+pub struct SlackError;
+
+impl fmt::Display for SlackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("failed to post to the Slack webhook")
+    }
+}
 
 pub struct SlackApi;
 
 impl SlackApi {
-    pub fn construct_slack_message(message: &Vec<String>) -> Slack {
-        let text_lines = message.join("\n");
+    pub fn construct_digest(pulls: &[ChangeRequest], locale: Locale, now: NaiveDateTime) -> Slack {
+        let max_pulls = (MAX_BLOCKS - 3) / BLOCKS_PER_PULL;
+        let title = locale.label(Label::OpenPullRequest);
+
+        let mut blocks: Vec<Block> = vec![Header::new(title).into()];
+
+        for pull in pulls.iter().take(max_pulls) {
+            blocks.extend(Self::pull_request_blocks(&pull.text_line().locale(locale), now));
+        }
+
+        if pulls.len() > max_pulls {
+            blocks.push(Context::new(vec![
+                Element::mrkdwn(&locale.more(pulls.len() - max_pulls)),
+            ]).into());
+        }
+
+        blocks.push(Context::new(vec![Element::mrkdwn(&locale.open_pull_requests(pulls.len()))]).into());
+
+        let mut text_lines = vec![format!("*{}*", title)];
+        text_lines.extend(pulls.iter().map(|pull| mrkdwn::message(&pull.text_line().locale(locale))));
 
         Slack {
-            blocks: vec![
-                Message {
-                    r#type: "section".to_string(),
-                    text: Text {
-                        r#type: "mrkdwn".to_string(),
-                        text: text_lines.to_string(),
-                    },
-                }
-            ]
+            text: Some(text_lines.join("\n")),
+            blocks,
+        }
+    }
+
+    pub fn construct_templated_digest(pulls: &[ChangeRequest], templates: &Templates, locale: Locale, now: NaiveDateTime) -> anyhow::Result<Slack> {
+        let text_lines = pulls.iter().map(|pull| pull.text_line().locale(locale)).collect::<Vec<TextLine>>();
+        let views = text_lines.iter().map(|text_line| text_line.view(now)).collect::<Vec<_>>();
+        let digest = DigestView {
+            count: pulls.len(),
+            count_text: locale.open_pull_requests(pulls.len()),
+            generated_at: locale.format_datetime(&now),
+            labels: locale.labels(),
+            pulls: &views,
+        };
+
+        let header = templates.render_header(&digest)?;
+        let pull_texts = views
+            .iter()
+            .map(|view| templates.render_pull_request(view))
+            .collect::<anyhow::Result<Vec<String>>>()?;
+        let footer = templates.render_footer(&digest)?;
+
+        let max_pulls = (MAX_BLOCKS - 3) / 2;
+
+        let mut blocks: Vec<Block> = Vec::new();
+
+        if !header.is_empty() {
+            blocks.push(Section::new(Text::mrkdwn(&text::truncate(&header, MAX_SECTION_CHARS))).into());
+        }
+
+        for text in pull_texts.iter().take(max_pulls) {
+            blocks.push(Section::new(Text::mrkdwn(&text::truncate(text, MAX_SECTION_CHARS))).into());
+            blocks.push(Block::Divider);
         }
+
+        if pulls.len() > max_pulls {
+            blocks.push(Context::new(vec![
+                Element::mrkdwn(&locale.more(pulls.len() - max_pulls)),
+            ]).into());
+        }
+
+        if !footer.is_empty() {
+            blocks.push(Context::new(vec![Element::mrkdwn(&text::truncate(&footer, MAX_SECTION_CHARS))]).into());
+        }
+
+        let text_lines = [vec![header], pull_texts, vec![footer]]
+            .concat()
+            .into_iter()
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<String>>();
+
+        Ok(Slack {
+            text: Some(text_lines.join("\n")),
+            blocks,
+        })
     }
 
-    pub async fn send_message(slack: &Slack) -> Result<(), reqwest::Error>{
-        let client = reqwest::Client::new();
+    fn pull_request_blocks(text_line: &TextLine, now: NaiveDateTime) -> Vec<Block> {
+        let locale = text_line.locale;
+        let pull = text_line.pull;
 
-        let webhook_uri = env::var("WEBHOOK_URI").unwrap();
+        let reviewers = text_line
+            .reviewer_states()
+            .iter()
+            .map(|(reviewer, state)| format!("{} {}", state.shortcode(), mrkdwn::escape(&reviewer.login)))
+            .collect::<Vec<String>>();
 
-        let message = serde_json::json!(slack);
+        let section = Section::new(Text::mrkdwn(&format!(
+                "*{}*\n`{}#{}`",
+                mrkdwn::link(&pull.html_url, &pull.short_title()),
+                mrkdwn::escape(&text_line.repo.full_name),
+                pull.number
+            )))
+            .fields(vec![
+                Text::mrkdwn(&format!("*{}*\n{}", locale.label(Label::Status), mrkdwn::escape(&pull.state))),
+                Text::mrkdwn(&format!(
+                    "*{}*\n{}",
+                    locale.label(Label::Reviewers),
+                    if reviewers.is_empty() { locale.label(Label::NoReviewers).to_string() } else { reviewers.join("\n") }
+                )),
+            ])
+            .accessory(Element::link_button(locale.label(Label::OpenButton), &pull.html_url));
 
-        let response = client
-            .post(webhook_uri)
-            .json(&message)
-            .send()
-            .await?;
+        let mut context = Vec::new();
+        if let Some(avatar_url) = &pull.user.avatar_url {
+            context.push(Element::image(avatar_url, &pull.user.login));
+        }
+        context.push(Element::mrkdwn(&format!(
+            "{} · {}",
+            locale.created_by(&mrkdwn::link(&pull.user.html_url, &pull.user.login), &text_line.created_at()),
+            locale.relative_age(&text_line.age(now))
+        )));
 
-        if response.status().is_success() == false {
-            println!("Failed to send notification: {:?}", response.text().await?);
+        vec![section.into(), Context::new(context).into(), Block::Divider]
+    }
+
+    pub fn block_kit_builder_url(slack: &Slack) -> String {
+        Self::block_kit_builder_url_for(&serde_json::json!(slack))
+    }
+
+    fn block_kit_builder_url_for(payload: &Value) -> String {
+        let payload = payload.to_string();
+
+        let encoded = payload
+            .bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')' => {
+                    (byte as char).to_string()
+                },
+                _ => format!("%{:02X}", byte),
+            })
+            .collect::<String>();
+
+        format!("{}#{}", BLOCK_KIT_BUILDER_URI, encoded)
+    }
+
+    /// Auth failures, a revoked webhook (404) and rate limits keep their
+    /// usual classification, and 429s are retried; any other 4xx carries
+    /// Slack's reason for rejecting the payload, such as `invalid_blocks`.
+    pub async fn post(webhook_uri: &str, payload: &Slack) -> Result<(), Error> {
+        let request = reqwest::Client::new().post(webhook_uri).json(payload);
+
+        match notifier::send_with_retry(WEBHOOK, request).await {
+            Ok(_) => Ok(()),
+            Err(Error::Status { status, body, .. }) if status.is_client_error() => Err(Error::SlackRejected { status, body }),
+            Err(e) => Err(e),
         }
+    }
+
+    /// A live webhook answers an empty payload with 400 instead of posting it.
+    pub async fn check_webhook(webhook_uri: &str) -> Result<(), Error> {
+        let request = reqwest::Client::new().post(webhook_uri).json(&serde_json::json!({}));
 
-        Ok(())
+        match notifier::send(WEBHOOK, request).await {
+            Ok(_) | Err(Error::Status { status: StatusCode::BAD_REQUEST, .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+pub struct SlackNotifier {
+    name: String,
+    webhook_uri: String,
+    locale: Locale,
+    templates: Option<Templates>,
+}
+
+impl SlackNotifier {
+    pub fn new(name: &str, webhook_uri: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            webhook_uri: webhook_uri.to_string(),
+            locale: Locale::default(),
+            templates: None,
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn templates(mut self, templates: Templates) -> Self {
+        self.templates = Some(templates);
+        self
+    }
+
+    pub fn construct(&self, digest: &Digest) -> anyhow::Result<Slack> {
+        match &self.templates {
+            Some(templates) => SlackApi::construct_templated_digest(&digest.pulls, templates, self.locale, digest.generated_at),
+            None => Ok(SlackApi::construct_digest(&digest.pulls, self.locale, digest.generated_at)),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, digest: &Digest) -> anyhow::Result<Rendered> {
+        let slack = self.construct(digest)?;
+
+        Ok(Rendered {
+            text: slack.text.clone().unwrap_or_default(),
+            payload: Payload::Slack(slack),
+        })
+    }
+
+    async fn send(&self, rendered: &Rendered) -> anyhow::Result<()> {
+        let Payload::Slack(slack) = &rendered.payload else {
+            return Err(notifier::unexpected_payload(&self.name));
+        };
+
+        SlackApi::post(&self.webhook_uri, slack).await.context(SlackError)
+    }
+
+    fn preview_url(&self, rendered: &Rendered) -> Option<String> {
+        match &rendered.payload {
+            Payload::Slack(slack) => Some(SlackApi::block_kit_builder_url(slack)),
+            _ => None,
+        }
+    }
+
+    async fn check(&self) -> anyhow::Result<String> {
+        SlackApi::check_webhook(&self.webhook_uri).await?;
+
+        Ok("webhook reachable".to_string())
     }
 }
 
@@ -48,16 +283,13 @@ mod slack_api_test{
 
     use super::*;
     use crate::domain::model::{
-        slack::{
-            Message,
-            Text,
-        },
+        slack::Element,
         github::{
             PullRequest,
             Repository,
             Reviewers,
             Review,
-            TextLine,
+            OpenPullRequest,
             User
        }
     };
@@ -77,18 +309,22 @@ mod slack_api_test{
             user: User {
                 login: "test".to_string(),
                 html_url: "https://github.com/reo0306".to_string(),
+                avatar_url: None,
             },
             created_at: "2024-07-16T20:09:31Z".to_string(),
+            draft: false,
         },
         Reviewers {
             users: vec![
                 User {
                     login: "test".to_string(),
                     html_url: "https://github.com/reo0306".to_string(),
+                    avatar_url: None,
                 },
                 User {
                     login: "test2".to_string(),
                     html_url: "https://github.com/reo0306".to_string(),
+                    avatar_url: None,
                 },
             ],
         },
@@ -97,6 +333,7 @@ mod slack_api_test{
                 user: User {
                     login: "test".to_string(),
                     html_url: "https://github.com/reo0306".to_string(),
+                    avatar_url: None,
                 },
                 state: "APPROVED".to_string(),
             },
@@ -104,6 +341,7 @@ mod slack_api_test{
                 user: User {
                     login: "test2".to_string(),
                     html_url: "https://github.com/reo0306".to_string(),
+                    avatar_url: None,
                 },
                 state: "COMMENTED".to_string(),
             },
@@ -124,18 +362,22 @@ mod slack_api_test{
             user: User {
                 login: "test".to_string(),
                 html_url: "https://github.com/reo0306".to_string(),
+                avatar_url: None,
             },
             created_at: "2024-07-16T20:09:31Z".to_string(),
+            draft: false,
         },
         Reviewers {
             users: vec![
                 User {
                     login: "test".to_string(),
                     html_url: "https://github.com/reo0306".to_string(),
+                    avatar_url: None,
                 },
                 User {
                     login: "test2".to_string(),
                     html_url: "https://github.com/reo0306".to_string(),
+                    avatar_url: None,
                 },
             ],
         },
@@ -149,88 +391,348 @@ mod slack_api_test{
         #[case] reviews: Vec<Review>,
         #[case] result: String,
     ) {
-        let text_line = TextLine::new(&repo, &pull, reviewers, reviews);
+        let change_request = ChangeRequest::try_from(OpenPullRequest::new(&repo, pull, reviewers, reviews)).unwrap();
+        let text_line = change_request.text_line();
 
-        let mut message = Vec::new();
-        message.push(text_line.message());
-        message.push(text_line.message());
+        let message = [mrkdwn::message(&text_line), mrkdwn::message(&text_line)];
 
         let text_lines = message.join("\n");
 
         assert_eq!(result, text_lines);
     }
 
-    #[rstest]
-    #[case(
-        Repository {
+    fn now() -> NaiveDateTime {
+        NaiveDateTime::parse_from_str("2024-07-19T08:09:31Z", "%Y-%m-%dT%H:%M:%SZ").unwrap()
+    }
+
+    #[test]
+    fn test_block_kit_builder_url() {
+        let slack = Slack {
+            text: None,
+            blocks: vec![Section::new(Text::mrkdwn("*Open Pull Request*\n<https://github.com|a & b>")).into()],
+        };
+
+        assert_eq!(
+            "https://app.slack.com/block-kit-builder/#%7B%22blocks%22%3A%5B%7B%22text%22%3A%7B%22text%22%3A%22*Open%20Pull%20Request*%5Cn%3Chttps%3A%2F%2Fgithub.com%7Ca%20%26%20b%3E%22%2C%22type%22%3A%22mrkdwn%22%7D%2C%22type%22%3A%22section%22%7D%5D%7D",
+            SlackApi::block_kit_builder_url(&slack)
+        );
+    }
+
+    #[test]
+    fn test_construct_digest() {
+        let repo = Repository {
             name: "gospo".to_string(),
             full_name: "reo0306/gospo".to_string(),
             url: "https://api.github.com/repos/reo0306/gospo".to_string(),
-        },
-        PullRequest {
+        };
+        let user = |login: &str| User {
+            login: login.to_string(),
+            html_url: format!("https://github.com/{}", login),
+            avatar_url: Some(format!("https://avatars.githubusercontent.com/{}", login)),
+        };
+        let pull: ChangeRequest = OpenPullRequest::new(
+            &repo,
+            PullRequest {
+                html_url: "https://github.com/reo0306/gospo/pull/1".to_string(),
+                number: 1,
+                state: "open".to_string(),
+                title: "Test".to_string(),
+                user: user("reo0306"),
+                created_at: "2024-07-16T20:09:31Z".to_string(),
+                draft: false,
+            },
+            Reviewers { users: vec![user("test"), user("test2")] },
+            vec![Review { user: user("test"), state: "APPROVED".to_string() }],
+        ).try_into().unwrap();
+
+        let slack = SlackApi::construct_digest(&[pull], Locale::En, now());
+
+        assert_eq!(
+            serde_json::json!({
+                "text": "*Open Pull Request*\n*Test - <https://github.com/reo0306/gospo/pull/1|reo0306/gospo#1>*\nunapproved reviewers - test2\n*open* - Created by <https://github.com/reo0306|reo0306> on 2024-07-16 20:09:31",
+                "blocks": [
+                    { "type": "header", "text": { "type": "plain_text", "text": "Open Pull Request" } },
+                    {
+                        "type": "section",
+                        "text": { "type": "mrkdwn", "text": "*<https://github.com/reo0306/gospo/pull/1|Test>*\n`reo0306/gospo#1`" },
+                        "fields": [
+                            { "type": "mrkdwn", "text": "*Status*\nopen" },
+                            { "type": "mrkdwn", "text": "*Reviewers*\n:white_check_mark: test\n:hourglass_flowing_sand: test2" },
+                        ],
+                        "accessory": {
+                            "type": "button",
+                            "text": { "type": "plain_text", "text": "Open PR" },
+                            "url": "https://github.com/reo0306/gospo/pull/1",
+                        },
+                    },
+                    {
+                        "type": "context",
+                        "elements": [
+                            { "type": "image", "image_url": "https://avatars.githubusercontent.com/reo0306", "alt_text": "reo0306" },
+                            { "type": "mrkdwn", "text": "Created by <https://github.com/reo0306|reo0306> on 2024-07-16 20:09:31 · 2 days ago" },
+                        ],
+                    },
+                    { "type": "divider" },
+                    { "type": "context", "elements": [{ "type": "mrkdwn", "text": "1 open pull request" }] },
+                ],
+            }),
+            serde_json::json!(slack)
+        );
+    }
+
+    #[test]
+    fn test_construct_digest_block_limit() {
+        let repo = Repository {
+            name: "gospo".to_string(),
+            full_name: "reo0306/gospo".to_string(),
+            url: "https://api.github.com/repos/reo0306/gospo".to_string(),
+        };
+        let pulls = (1..=20)
+            .map(|number| ChangeRequest::try_from(OpenPullRequest::new(
+                &repo,
+                PullRequest {
+                    html_url: format!("https://github.com/reo0306/gospo/pull/{}", number),
+                    number,
+                    state: "open".to_string(),
+                    title: "Test".to_string(),
+                    user: User {
+                        login: "test".to_string(),
+                        html_url: "https://github.com/reo0306".to_string(),
+                        avatar_url: None,
+                    },
+                    created_at: "2024-07-16T20:09:31Z".to_string(),
+                    draft: false,
+                },
+                Reviewers { users: Vec::new() },
+                Vec::new(),
+            )).unwrap())
+            .collect::<Vec<ChangeRequest>>();
+
+        let slack = SlackApi::construct_digest(&pulls, Locale::En, now());
+
+        assert!(slack.blocks.len() <= MAX_BLOCKS);
+        assert_eq!(
+            Some(&Block::Context(Context::new(vec![Element::mrkdwn("and 5 more")]))),
+            slack.blocks.get(slack.blocks.len() - 2)
+        );
+    }
+
+    #[test]
+    fn test_text_line_escapes_untrusted_data() {
+        let repo = Repository {
+            name: "gospo".to_string(),
+            full_name: "reo0306/gospo".to_string(),
+            url: "https://api.github.com/repos/reo0306/gospo".to_string(),
+        };
+        let pull = PullRequest {
             html_url: "https://github.com/reo0306/gospo/pull/1".to_string(),
             number: 1,
             state: "open".to_string(),
-            title: "Test".to_string(),
+            title: "<!channel> Fix <https://evil.example|login> & logout".to_string(),
             user: User {
-                login: "test".to_string(),
+                login: "<@U024BE7LH>".to_string(),
                 html_url: "https://github.com/reo0306".to_string(),
+                avatar_url: None,
             },
             created_at: "2024-07-16T20:09:31Z".to_string(),
-        },
-        Reviewers {
-            users: vec![
-                User {
-                    login: "test".to_string(),
-                    html_url: "https://github.com/reo0306".to_string(),
-                },
-                User {
-                    login: "test2".to_string(),
-                    html_url: "https://github.com/reo0306".to_string(),
-                },
-            ],
-        },
-        vec![
-            Review {
-                user: User {
-                    login: "test".to_string(),
-                    html_url: "https://github.com/reo0306".to_string(),
-                },
-                state: "APPROVED".to_string(),
-            },
-            Review {
-                user: User {
-                    login: "test2".to_string(),
-                    html_url: "https://github.com/reo0306".to_string(),
-                },
-                state: "COMMENTED".to_string(),
-            },
-        ],
-    )]
-    fn test_slack(
-        #[case] repo: Repository,
-        #[case] pull: PullRequest,
-        #[case] reviewers: Reviewers,
-        #[case] reviews: Vec<Review>,
-    ) {
-        let text_lines = TextLine::new(&repo, &pull, reviewers, reviews);
+            draft: false,
+        };
+        let change_request = ChangeRequest::try_from(OpenPullRequest::new(&repo, pull, Reviewers { users: Vec::new() }, Vec::new())).unwrap();
+        let text_line = change_request.text_line();
+
+        assert_eq!(
+            "*&lt;!channel&gt; Fix &lt;https://evil.example|login&gt; &amp; logout - <https://github.com/reo0306/gospo/pull/1|reo0306/gospo#1>*\n\n*open* - Created by <https://github.com/reo0306|&lt;@U024BE7LH&gt;> on 2024-07-16 20:09:31",
+            mrkdwn::message(&text_line)
+        );
+
+        let slack = SlackApi::construct_digest(std::slice::from_ref(&change_request), Locale::En, now());
+        let payload = serde_json::json!(slack).to_string();
+
+        assert!(!payload.contains("<!channel>"));
+        assert!(!payload.contains("<@U024BE7LH>"));
+        assert!(!payload.contains("<https://evil.example"));
+    }
 
-        let mut message = Vec::new();
-        message.push(text_lines.message());
+    #[test]
+    fn test_construct_templated_digest_truncates_footer() {
+        let dir = std::env::temp_dir().join("pullrequest-slack-bot-footer-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("footer.tera"), "{% for i in range(end=4000) %}x{% endfor %}").unwrap();
+
+        let templates = Templates::from_dir(&dir.to_string_lossy()).unwrap();
+        let slack = SlackApi::construct_templated_digest(&[], &templates, Locale::En, now()).unwrap();
+
+        match slack.blocks.last() {
+            Some(Block::Context(context)) => assert_eq!(Element::mrkdwn(&format!("{}…", "x".repeat(MAX_SECTION_CHARS - 1))), context.elements[0]),
+            other => panic!("unexpected {:?}", other),
+        }
 
-        let slack = SlackApi::construct_slack_message(&message);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_construct_digest_ja() {
+        let repo = Repository {
+            name: "gospo".to_string(),
+            full_name: "reo0306/gospo".to_string(),
+            url: "https://api.github.com/repos/reo0306/gospo".to_string(),
+        };
+        let user = |login: &str| User {
+            login: login.to_string(),
+            html_url: format!("https://github.com/{}", login),
+            avatar_url: None,
+        };
+        let pull: ChangeRequest = OpenPullRequest::new(
+            &repo,
+            PullRequest {
+                html_url: "https://github.com/reo0306/gospo/pull/1".to_string(),
+                number: 1,
+                state: "open".to_string(),
+                title: "Test".to_string(),
+                user: user("reo0306"),
+                created_at: "2024-07-16T20:09:31Z".to_string(),
+                draft: false,
+            },
+            Reviewers { users: vec![user("test")] },
+            Vec::new(),
+        ).try_into().unwrap();
+
+        let slack = SlackApi::construct_digest(&[pull], Locale::Ja, now());
 
         assert_eq!(
-            vec![
-                Message {
-                    r#type: "section".to_string(),
-                    text: Text {
-                        r#type: "mrkdwn".to_string(),
-                        text: text_lines.message().to_string(),
-                    }
-                }
-            ],
-            slack.blocks
+            Some("*オープン中のプルリクエスト*\n*Test - <https://github.com/reo0306/gospo/pull/1|reo0306/gospo#1>*\n未承認のレビュアー - test\n*open* - <https://github.com/reo0306|reo0306> が 2024年07月16日 20:09 に作成".to_string()),
+            slack.text
+        );
+        assert_eq!(
+            Some(&Block::Context(Context::new(vec![
+                Element::mrkdwn("<https://github.com/reo0306|reo0306> が 2024年07月16日 20:09 に作成 · 2日前"),
+            ]))),
+            slack.blocks.get(2)
+        );
+        assert_eq!(
+            Some(&Block::Context(Context::new(vec![Element::mrkdwn("オープン中のプルリクエスト 1 件")]))),
+            slack.blocks.last()
         );
     }
+
+    #[rstest]
+    #[case(200, "ok", "ok")]
+    #[case(400, "invalid_blocks", "rejected")]
+    #[case(410, "channel_is_archived", "rejected")]
+    #[case(403, "invalid_token", "unauthorized")]
+    #[case(404, "no_service", "not_found")]
+    #[case(500, "rollup_error", "status")]
+    #[tokio::test]
+    async fn test_slack_notifier_notify(#[case] status: usize, #[case] body: &str, #[case] expected: &str) {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/T000/B000/XXXX")
+            .match_header("content-type", "application/json")
+            .with_status(status)
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let notifier = SlackNotifier::new("team", &format!("{}/services/T000/B000/XXXX", server.url()));
+        let report = notifier.notify(&Digest::new(Vec::new(), now())).await;
+
+        assert_eq!("team", report.notifier);
+
+        let kind = match &report.result {
+            Ok(()) => "ok",
+            Err(e) => {
+                assert!(e.downcast_ref::<SlackError>().is_some());
+                assert!(!format!("{:?}", e).contains("XXXX"), "{:?}", e);
+
+                match e.downcast_ref::<Error>() {
+                    Some(Error::SlackRejected { body: reason, .. }) => {
+                        assert_eq!(body, reason);
+                        "rejected"
+                    },
+                    Some(Error::Unauthorized { .. }) => "unauthorized",
+                    Some(Error::NotFound { .. }) => "not_found",
+                    Some(Error::Status { .. }) => "status",
+                    other => panic!("unexpected {:?}", other),
+                }
+            },
+        };
+
+        assert_eq!(expected, kind);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_slack_notifier_retries_after_rate_limit() {
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("POST", "/services/T000/B000/XXXX")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .with_body("rate_limited")
+            .expect(1)
+            .create_async()
+            .await;
+        let accepted = server
+            .mock("POST", "/services/T000/B000/XXXX")
+            .with_status(200)
+            .with_body("ok")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let notifier = SlackNotifier::new("team", &format!("{}/services/T000/B000/XXXX", server.url()));
+        let report = notifier.notify(&Digest::new(Vec::new(), now())).await;
+
+        assert!(report.result.is_ok());
+
+        limited.assert_async().await;
+        accepted.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_slack_notifier_gives_up_after_retries() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/T000/B000/XXXX")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .with_body("rate_limited")
+            .expect(notifier::MAX_RETRIES + 1)
+            .create_async()
+            .await;
+
+        let notifier = SlackNotifier::new("team", &format!("{}/services/T000/B000/XXXX", server.url()));
+        let report = notifier.notify(&Digest::new(Vec::new(), now())).await;
+
+        assert!(matches!(
+            report.result.as_ref().map_err(|e| e.downcast_ref::<Error>()),
+            Err(Some(Error::RateLimited { retry_after: Some(0), .. }))
+        ));
+
+        mock.assert_async().await;
+    }
+
+    #[rstest]
+    #[case(400, "invalid_payload", true)]
+    #[case(404, "no_service", false)]
+    #[tokio::test]
+    async fn test_check_webhook(#[case] status: usize, #[case] body: &str, #[case] ok: bool) {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/services/T000/B000/XXXX")
+            .with_status(status)
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let result = SlackApi::check_webhook(&format!("{}/services/T000/B000/XXXX", server.url())).await;
+
+        assert_eq!(ok, result.is_ok());
+        if let Err(e) = result {
+            assert!(!e.to_string().contains("XXXX"), "{}", e);
+        }
+
+        mock.assert_async().await;
+    }
 }
\ No newline at end of file
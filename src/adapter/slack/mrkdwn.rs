@@ -0,0 +1,104 @@
+//! Escaping for text interpolated into Slack mrkdwn.
+//!
+//! Slack only treats `&`, `<` and `>` as control characters, so escaping them
+//! is enough to stop `<!channel>`, `<@U123>` or `<url|label>` from being
+//! injected through pull request titles or logins.
+
+use crate::domain::model::{change_request::TextLine, locale::Label};
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Percent-encodes the characters that would end a `<url|label>` link early.
+pub fn escape_url(url: &str) -> String {
+    let mut escaped = String::with_capacity(url.len());
+
+    for c in url.chars() {
+        match c {
+            '|' => escaped.push_str("%7C"),
+            '<' => escaped.push_str("%3C"),
+            '>' => escaped.push_str("%3E"),
+            ' ' => escaped.push_str("%20"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Slack splits `<url|label>` at the first `|`, so only the url needs its
+/// `|` encoded; any `|` in the label is shown as is.
+pub fn link(url: &str, label: &str) -> String {
+    format!("<{}|{}>", escape_url(url), escape(label))
+}
+
+/// The three-line mrkdwn summary of a pull request used for the fallback
+/// text: title and link, pending reviewers, state and author.
+pub fn message(text_line: &TextLine) -> String {
+    let pull = text_line.pull;
+    let locale = text_line.locale;
+    let unapproved = text_line.unapproved_reviewer_logins();
+
+    [
+        format!(
+            "*{} - {}*",
+            escape(&pull.short_title()),
+            link(&pull.html_url, &format!("{}#{}", text_line.repo.full_name, pull.number)),
+        ),
+        if unapproved.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "{} - {}",
+                locale.label(Label::UnapprovedReviewers),
+                unapproved.iter().map(|user| escape(user)).collect::<Vec<String>>().join(" ")
+            )
+        },
+        format!(
+            "*{}* - {}",
+            escape(&pull.state),
+            locale.created_by(&link(&pull.user.html_url, &pull.user.login), &text_line.created_at()),
+        ),
+    ].join("\n")
+}
+
+#[cfg(test)]
+mod mrkdwn_test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("Fix login", "Fix login")]
+    #[case("<!channel> please review", "&lt;!channel&gt; please review")]
+    #[case("<!here|here>", "&lt;!here|here&gt;")]
+    #[case("cc <@U024BE7LH>", "cc &lt;@U024BE7LH&gt;")]
+    #[case("<https://evil.example|docs>", "&lt;https://evil.example|docs&gt;")]
+    #[case("Tom & Jerry &amp;", "Tom &amp; Jerry &amp;amp;")]
+    #[case("a > b < c", "a &gt; b &lt; c")]
+    #[case("日本語のタイトル", "日本語のタイトル")]
+    fn test_escape(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(expected, escape(text));
+    }
+
+    #[rstest]
+    #[case("https://github.com/reo0306/gospo/pull/1", "Test", "<https://github.com/reo0306/gospo/pull/1|Test>")]
+    #[case("https://github.com/reo0306/gospo/pull/1", "feat|fix", "<https://github.com/reo0306/gospo/pull/1|feat|fix>")]
+    #[case("https://github.com/reo0306/gospo/pull/1", "<!channel>", "<https://github.com/reo0306/gospo/pull/1|&lt;!channel&gt;>")]
+    #[case("https://example.com/a|b>c d", "x", "<https://example.com/a%7Cb%3Ec%20d|x>")]
+    fn test_link(#[case] url: &str, #[case] label: &str, #[case] expected: &str) {
+        assert_eq!(expected, link(url, label));
+    }
+}
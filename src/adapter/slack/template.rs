@@ -0,0 +1,293 @@
+use std::{collections::HashMap, path::Path};
+use anyhow::{Context as _, Result};
+use serde::Serialize;
+use tera::{Context, Tera, Value};
+
+use crate::adapter::{html, slack::mrkdwn};
+use crate::domain::text;
+use crate::domain::model::{
+    change_request::TextLineView,
+    locale::Labels,
+};
+
+const HEADER: &str = "header";
+const PULL_REQUEST: &str = "pull_request";
+const FOOTER: &str = "footer";
+const EXTENSION: &str = "tera";
+
+const DEFAULT_HEADER: &str = "*{{ labels.open_pull_request }}*";
+const DEFAULT_PULL_REQUEST: &str = "\
+*{{ pull.title | shorten(length=200) }} - {{ slack_link(url=pull.html_url, label=repo.full_name ~ \"#\" ~ pull.number) | safe }}*
+{% if unapproved_reviewers %}{{ labels.unapproved_reviewers }} - {{ unapproved_reviewers | join(sep=\" \") }}{% endif %}
+*{{ status }}* - {{ created_by | safe }}";
+const DEFAULT_FOOTER: &str = "";
+
+#[derive(Serialize, Debug)]
+pub struct DigestView<'a> {
+    pub count: usize,
+    pub count_text: String,
+    pub generated_at: String,
+    pub labels: Labels,
+    pub pulls: &'a [TextLineView<'a>],
+}
+
+pub struct Templates {
+    tera: Tera,
+}
+
+impl Default for Templates {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Templates {
+    pub fn new() -> Self {
+        let mut tera = Tera::default();
+
+        // Every `{{ }}` is escaped for mrkdwn unless piped through `safe`.
+        tera.autoescape_on(vec![""]);
+        tera.set_escape_fn(mrkdwn::escape);
+        tera.register_filter("shorten", shorten);
+        tera.register_function("slack_link", slack_link);
+
+        for (name, template) in [(HEADER, DEFAULT_HEADER), (PULL_REQUEST, DEFAULT_PULL_REQUEST), (FOOTER, DEFAULT_FOOTER)] {
+            tera.add_raw_template(name, template).expect("built-in template must parse");
+        }
+
+        Self { tera }
+    }
+
+    /// Loads `header.tera`, `pull_request.tera` and `footer.tera` from `dir`,
+    /// keeping the built-in template for any file that does not exist.
+    pub fn from_dir(dir: &str) -> Result<Self> {
+        let mut templates = Self::new();
+
+        for name in [HEADER, PULL_REQUEST, FOOTER] {
+            let path = Path::new(dir).join(format!("{}.{}", name, EXTENSION));
+
+            if path.exists() {
+                templates.tera
+                    .add_template_file(&path, Some(name))
+                    .with_context(|| format!("failed to load template {}", path.display()))?;
+            }
+        }
+
+        Ok(templates)
+    }
+
+    pub fn render_header(&self, digest: &DigestView) -> Result<String> {
+        self.render(HEADER, &Context::from_serialize(digest)?)
+    }
+
+    /// Besides the view, the context carries the localized `created_by`
+    /// line (mrkdwn, so output it with `safe`), the pull request's `status`
+    /// and its `reviewers` with their state emoji.
+    pub fn render_pull_request(&self, view: &TextLineView) -> Result<String> {
+        let text_line = view.line;
+        let pull = text_line.pull;
+
+        let mut context = Context::from_serialize(view)?;
+        context.insert(
+            "created_by",
+            &text_line.locale.created_by(&mrkdwn::link(&pull.user.html_url, &pull.user.login), &view.created_at),
+        );
+        context.insert("status", &pull.state);
+        context.insert("reviewers", &html::reviewers(text_line, |login| login.to_string()));
+
+        self.render(PULL_REQUEST, &context)
+    }
+
+    pub fn render_footer(&self, digest: &DigestView) -> Result<String> {
+        self.render(FOOTER, &Context::from_serialize(digest)?)
+    }
+
+    fn render(&self, name: &str, context: &Context) -> Result<String> {
+        let rendered = self.tera
+            .render(name, context)
+            .with_context(|| format!("failed to render template {}", name))?;
+
+        Ok(rendered.trim_end_matches('\n').to_string())
+    }
+}
+
+fn shorten(value: &Value, args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let text = tera::try_get_value!("shorten", "value", String, value);
+    let length = match args.get("length") {
+        Some(length) => tera::try_get_value!("shorten", "length", usize, length),
+        None => return Err("filter `shorten` expected an arg called `length`".into()),
+    };
+
+    Ok(Value::String(text::truncate(&text, length)))
+}
+
+fn slack_link(args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let arg = |name: &str| match args.get(name) {
+        Some(Value::String(value)) => Ok(value.to_string()),
+        Some(value) => Ok(value.to_string()),
+        None => Err(tera::Error::msg(format!("function `slack_link` expected an arg called `{}`", name))),
+    };
+
+    Ok(Value::String(mrkdwn::link(&arg("url")?, &arg("label")?)))
+}
+
+#[cfg(test)]
+mod template_test {
+    use std::{env, fs};
+    use chrono::NaiveDateTime;
+
+    use super::*;
+    use crate::domain::model::{change_request::ChangeRequest, locale::Locale};
+    use crate::domain::model::github::{
+        OpenPullRequest,
+        PullRequest,
+        Repository,
+        Reviewers,
+        Review,
+        User
+    };
+
+    fn user(login: &str) -> User {
+        User {
+            login: login.to_string(),
+            html_url: format!("https://github.com/{}", login),
+            avatar_url: None,
+        }
+    }
+
+    fn now() -> NaiveDateTime {
+        NaiveDateTime::parse_from_str("2024-07-19T08:09:31Z", "%Y-%m-%dT%H:%M:%SZ").unwrap()
+    }
+
+    #[test]
+    fn test_default_template_matches_text_line() {
+        let repo = Repository {
+            name: "gospo".to_string(),
+            full_name: "reo0306/gospo".to_string(),
+            url: "https://api.github.com/repos/reo0306/gospo".to_string(),
+        };
+        let pull = PullRequest {
+            html_url: "https://github.com/reo0306/gospo/pull/1".to_string(),
+            number: 1,
+            state: "open".to_string(),
+            title: "<!channel> & Test".to_string(),
+            user: user("test"),
+            created_at: "2024-07-16T20:09:31Z".to_string(),
+            draft: false,
+        };
+
+        for locale in [Locale::En, Locale::Ja] {
+            for reviews in [Vec::new(), vec![Review { user: user("test"), state: "APPROVED".to_string() }]] {
+                let reviewers = Reviewers { users: vec![user("test"), user("test2")] };
+                let change_request = ChangeRequest::try_from(OpenPullRequest::new(&repo, pull.clone(), reviewers, reviews)).unwrap();
+                let text_line = change_request.text_line().locale(locale);
+
+                assert_eq!(
+                    mrkdwn::message(&text_line),
+                    Templates::new().render_pull_request(&text_line.view(now())).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_templates_from_dir() {
+        let dir = env::temp_dir().join("pullrequest-slack-bot-template-test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("header.tera"), "*{{ count }} PRs waiting*\n").unwrap();
+        fs::write(
+            dir.join("pull_request.tera"),
+            "{{ repo.name }} {{ pull.title }} ({{ age.days }}d) {% for r in reviewer_states %}{{ r.login }}:{{ r.state }} {% endfor %}\n",
+        ).unwrap();
+
+        let repo = Repository {
+            name: "gospo".to_string(),
+            full_name: "reo0306/gospo".to_string(),
+            url: "https://api.github.com/repos/reo0306/gospo".to_string(),
+        };
+        let pull = PullRequest {
+            html_url: "https://github.com/reo0306/gospo/pull/1".to_string(),
+            number: 1,
+            state: "open".to_string(),
+            title: "a < b".to_string(),
+            user: user("test"),
+            created_at: "2024-07-16T20:09:31Z".to_string(),
+            draft: false,
+        };
+        let change_request = ChangeRequest::try_from(OpenPullRequest::new(
+            &repo,
+            pull,
+            Reviewers { users: vec![user("test"), user("test2")] },
+            vec![Review { user: user("test"), state: "APPROVED".to_string() }],
+        )).unwrap();
+        let text_line = change_request.text_line();
+        let views = vec![text_line.view(now())];
+
+        let templates = Templates::from_dir(&dir.to_string_lossy()).unwrap();
+        let digest = DigestView {
+            count: 1,
+            count_text: Locale::En.open_pull_requests(1),
+            generated_at: now().to_string(),
+            labels: Locale::En.labels(),
+            pulls: &views,
+        };
+
+        assert_eq!("*1 PRs waiting*", templates.render_header(&digest).unwrap());
+        assert_eq!(
+            "gospo a &lt; b (2d) test:APPROVED test2:PENDING",
+            templates.render_pull_request(&views[0]).unwrap().trim_end()
+        );
+        assert_eq!("", templates.render_footer(&digest).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pull_request_context_strings() {
+        let dir = env::temp_dir().join("pullrequest-slack-bot-template-strings-test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("pull_request.tera"), "{{ status }} / {{ reviewers }} / {{ created_by | safe }}").unwrap();
+
+        let repo = Repository {
+            name: "gospo".to_string(),
+            full_name: "reo0306/gospo".to_string(),
+            url: "https://api.github.com/repos/reo0306/gospo".to_string(),
+        };
+        let pull = PullRequest {
+            html_url: "https://github.com/reo0306/gospo/pull/1".to_string(),
+            number: 1,
+            state: "open".to_string(),
+            title: "Test".to_string(),
+            user: user("test"),
+            created_at: "2024-07-16T20:09:31Z".to_string(),
+            draft: false,
+        };
+        let change_request = ChangeRequest::try_from(OpenPullRequest::new(
+            &repo,
+            pull,
+            Reviewers { users: vec![user("<b>"), user("test2")] },
+            vec![Review { user: user("<b>"), state: "APPROVED".to_string() }],
+        )).unwrap();
+        let text_line = change_request.text_line().locale(Locale::Ja);
+
+        let templates = Templates::from_dir(&dir.to_string_lossy()).unwrap();
+
+        assert_eq!(
+            "open / ✅ &lt;b&gt;, ⏳ test2 / <https://github.com/test|test> が 2024年07月16日 20:09 に作成",
+            templates.render_pull_request(&text_line.view(now())).unwrap()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_templates_from_dir_invalid() {
+        let dir = env::temp_dir().join("pullrequest-slack-bot-template-invalid-test");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("footer.tera"), "{% if %}").unwrap();
+
+        assert!(Templates::from_dir(&dir.to_string_lossy()).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
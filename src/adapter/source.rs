@@ -0,0 +1,46 @@
+use std::fmt;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use crate::domain::model::change_request::{ChangeRequest, Forge};
+
+#[async_trait]
+pub trait ChangeRequestSource: Send + Sync {
+    fn forge(&self) -> Forge;
+
+    /// Open change requests of the repositories matching `repos`
+    /// (`name` or `full_name`; empty means all).
+    async fn change_requests(&self, repos: &[String]) -> Result<Vec<ChangeRequest>>;
+
+    /// What `doctor` reports when the source's credentials work.
+    async fn check(&self) -> Result<String> {
+        Ok(format!("{} open change requests", self.change_requests(&[]).await?.len()))
+    }
+}
+
+/// Context attached to a source's errors, naming the forge that failed.
+#[derive(Debug)]
+pub struct SourceError {
+    pub forge: Forge,
+}
+
+impl fmt::Display for SourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to collect change requests from {}", self.forge)
+    }
+}
+
+/// Change requests of every source, in source order.
+pub async fn collect_all(sources: &[Box<dyn ChangeRequestSource>], repos: &[String]) -> Result<Vec<ChangeRequest>> {
+    let mut change_requests = Vec::new();
+
+    for source in sources {
+        change_requests.extend(
+            source.change_requests(repos)
+                .await
+                .with_context(|| SourceError { forge: source.forge() })?
+        );
+    }
+
+    Ok(change_requests)
+}
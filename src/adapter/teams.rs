@@ -0,0 +1,251 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+use crate::adapter::{
+    markdown,
+    notifier::{self, Notifier, Payload, Rendered},
+};
+use crate::domain::model::{
+    change_request::{ChangeRequest, TextLine},
+    digest::Digest,
+    locale::{Label, Locale},
+    teams::{Teams, CardElement, TextBlock, Container, FactSet, Fact, ActionSet, Action},
+};
+
+// Teams rejects webhook payloads above ~28KB; 20 pull requests with
+// full-length multibyte titles stay under it.
+const MAX_PULLS: usize = 20;
+
+pub struct TeamsApi;
+
+impl TeamsApi {
+    pub fn construct_digest(pulls: &[ChangeRequest], locale: Locale, now: NaiveDateTime) -> Teams {
+        let text_lines = pulls
+            .iter()
+            .map(|pull| pull.text_line().locale(locale))
+            .collect::<Vec<TextLine>>();
+
+        let mut body: Vec<CardElement> = vec![
+            TextBlock::new(locale.label(Label::OpenPullRequest)).size("Large").bolder().into()
+        ];
+
+        for text_line in text_lines.iter().take(MAX_PULLS) {
+            body.push(Self::pull_request_container(text_line, now).into());
+        }
+
+        if text_lines.len() > MAX_PULLS {
+            body.push(TextBlock::new(&locale.more(text_lines.len() - MAX_PULLS)).subtle().into());
+        }
+
+        body.push(TextBlock::new(&locale.open_pull_requests(text_lines.len())).subtle().into());
+
+        Teams::new(body)
+    }
+
+    fn pull_request_container(text_line: &TextLine, now: NaiveDateTime) -> Container {
+        let locale = text_line.locale;
+        let pull = text_line.pull;
+
+        let reviewers = text_line
+            .reviewer_states()
+            .iter()
+            .map(|(reviewer, state)| format!("{} {}", state.emoji(), markdown::escape(&reviewer.login)))
+            .collect::<Vec<String>>();
+
+        Container::new(vec![
+            TextBlock::new(&markdown::link(&pull.html_url, &pull.short_title()))
+                .bolder()
+                .into(),
+            TextBlock::new(&format!("{}#{}", markdown::escape(&text_line.repo.full_name), pull.number))
+                .subtle()
+                .into(),
+            FactSet {
+                facts: vec![
+                    Fact::new(locale.label(Label::Status), &markdown::escape(&pull.state)),
+                    Fact::new(
+                        locale.label(Label::Reviewers),
+                        &if reviewers.is_empty() { locale.label(Label::NoReviewers).to_string() } else { reviewers.join(", ") }
+                    ),
+                ],
+            }.into(),
+            TextBlock::new(&format!(
+                "{} · {}",
+                locale.created_by(&markdown::link(&pull.user.html_url, &pull.user.login), &text_line.created_at()),
+                locale.relative_age(&text_line.age(now))
+            )).subtle().into(),
+            ActionSet {
+                actions: vec![
+                    Action::OpenUrl { title: locale.label(Label::OpenButton).to_string(), url: pull.html_url.to_string() },
+                ],
+            }.into(),
+        ]).separator()
+    }
+}
+
+pub struct TeamsNotifier {
+    name: String,
+    webhook_uri: String,
+    locale: Locale,
+}
+
+impl TeamsNotifier {
+    pub fn new(name: &str, webhook_uri: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            webhook_uri: webhook_uri.to_string(),
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+#[async_trait]
+impl Notifier for TeamsNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, digest: &Digest) -> anyhow::Result<Rendered> {
+        let teams = TeamsApi::construct_digest(&digest.pulls, self.locale, digest.generated_at);
+
+        Ok(Rendered {
+            text: digest.text(self.locale),
+            payload: Payload::Teams(teams),
+        })
+    }
+
+    async fn send(&self, rendered: &Rendered) -> anyhow::Result<()> {
+        let Payload::Teams(teams) = &rendered.payload else {
+            return Err(notifier::unexpected_payload(&self.name));
+        };
+
+        Ok(notifier::post_json("Teams webhook", &self.webhook_uri, teams).await?)
+    }
+}
+
+#[cfg(test)]
+mod teams_test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::domain::model::change_request::fixture::{now, open_pull_request, open_pull_request_titled};
+
+    #[test]
+    fn test_construct_digest() {
+        let teams = TeamsApi::construct_digest(&[open_pull_request(1)], Locale::En, now());
+
+        assert_eq!(
+            serde_json::json!({
+                "type": "message",
+                "attachments": [{
+                    "contentType": "application/vnd.microsoft.card.adaptive",
+                    "contentUrl": null,
+                    "content": {
+                        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                        "type": "AdaptiveCard",
+                        "version": "1.4",
+                        "msteams": { "width": "Full" },
+                        "body": [
+                            { "type": "TextBlock", "text": "Open Pull Request", "wrap": true, "size": "Large", "weight": "Bolder" },
+                            {
+                                "type": "Container",
+                                "separator": true,
+                                "items": [
+                                    { "type": "TextBlock", "text": "[Test](https://github.com/reo0306/gospo/pull/1)", "wrap": true, "weight": "Bolder" },
+                                    { "type": "TextBlock", "text": "reo0306/gospo#1", "wrap": true, "isSubtle": true },
+                                    {
+                                        "type": "FactSet",
+                                        "facts": [
+                                            { "title": "Status", "value": "open" },
+                                            { "title": "Reviewers", "value": "✅ test, ⏳ test2" },
+                                        ]
+                                    },
+                                    {
+                                        "type": "TextBlock",
+                                        "text": "Created by [reo0306](https://github.com/reo0306) on 2024-07-16 20:09:31 · 2 days ago",
+                                        "wrap": true,
+                                        "isSubtle": true
+                                    },
+                                    {
+                                        "type": "ActionSet",
+                                        "actions": [
+                                            { "type": "Action.OpenUrl", "title": "Open PR", "url": "https://github.com/reo0306/gospo/pull/1" }
+                                        ]
+                                    },
+                                ]
+                            },
+                            { "type": "TextBlock", "text": "1 open pull request", "wrap": true, "isSubtle": true },
+                        ]
+                    }
+                }]
+            }),
+            serde_json::json!(teams)
+        );
+    }
+
+    #[test]
+    fn test_construct_digest_escapes_untrusted_data() {
+        let teams = TeamsApi::construct_digest(&[open_pull_request_titled("[click](https://evil.example) **now**")], Locale::En, now());
+        let payload = serde_json::json!(teams);
+
+        assert_eq!(
+            "[\\[click\\]\\(https://evil.example\\) \\*\\*now\\*\\*](https://github.com/reo0306/gospo/pull/1)",
+            payload["attachments"][0]["content"]["body"][1]["items"][0]["text"]
+        );
+    }
+
+    #[test]
+    fn test_construct_digest_limit() {
+        let pulls = (1..=35).map(open_pull_request).collect::<Vec<ChangeRequest>>();
+
+        let teams = TeamsApi::construct_digest(&pulls, Locale::Ja, now());
+        let body = &teams.attachments[0].content.body;
+
+        assert_eq!(1 + MAX_PULLS + 2, body.len());
+        assert_eq!(Some(&TextBlock::new("他 15 件").subtle().into()), body.get(1 + MAX_PULLS));
+        assert_eq!(Some(&TextBlock::new("オープン中のプルリクエスト 35 件").subtle().into()), body.last());
+    }
+
+    #[rstest]
+    #[case(200, "1", true)]
+    #[case(202, "", true)]
+    #[case(400, "Bad payload received by generic incoming webhook.", false)]
+    #[tokio::test]
+    async fn test_teams_notifier_notify(#[case] status: usize, #[case] body: &str, #[case] ok: bool) {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/webhookb2/xxxx")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"type":"message"}"#.to_string()
+            ))
+            .with_status(status)
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let notifier = TeamsNotifier::new("teams", &format!("{}/webhookb2/xxxx", server.url()));
+        let report = notifier.notify(&Digest::new(vec![open_pull_request(1)], now())).await;
+
+        assert_eq!("teams", report.notifier);
+        assert_eq!(ok, report.result.is_ok());
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_construct_digest_fits_size_limit() {
+        let limit = 28 * 1024;
+        let pulls = (1..=MAX_PULLS as u32 + 5)
+            .map(|number| ChangeRequest { title: "長".repeat(1000), ..open_pull_request(number) })
+            .collect::<Vec<ChangeRequest>>();
+
+        let payload = serde_json::to_vec(&TeamsApi::construct_digest(&pulls, Locale::Ja, now())).unwrap();
+
+        assert!(payload.len() < limit, "{} bytes", payload.len());
+    }
+}
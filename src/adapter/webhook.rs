@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use sha2::Sha256;
+
+use crate::adapter::{
+    error::Error,
+    notifier::{self, Notifier, Payload, Rendered},
+};
+use crate::domain::model::{
+    digest::Digest,
+    locale::Locale,
+    webhook::{DigestDocument, SCHEMA_VERSION},
+};
+
+pub const SIGNATURE_HEADER: &str = "X-Signature-256";
+pub const SCHEMA_VERSION_HEADER: &str = "X-Digest-Schema-Version";
+
+pub struct WebhookApi;
+
+impl WebhookApi {
+    /// GitHub-style `sha256=<hex>` HMAC of the exact request body.
+    pub fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+        mac.update(body);
+
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    pub async fn post(
+        uri: &str,
+        body: Vec<u8>,
+        secret: Option<&str>,
+        headers: &HashMap<String, String>,
+    ) -> Result<(), Error> {
+        let mut request = reqwest::Client::new()
+            .post(uri)
+            .header(CONTENT_TYPE, "application/json")
+            .header(SCHEMA_VERSION_HEADER, SCHEMA_VERSION.to_string());
+
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+
+        if let Some(secret) = secret {
+            request = request.header(SIGNATURE_HEADER, Self::sign(secret, &body));
+        }
+
+        // Custom headers often carry credentials, and so may the URL.
+        notifier::send("webhook", request.body(body)).await?;
+
+        Ok(())
+    }
+}
+
+pub struct WebhookNotifier {
+    name: String,
+    uri: String,
+    secret: Option<String>,
+    headers: HashMap<String, String>,
+    locale: Locale,
+}
+
+impl WebhookNotifier {
+    pub fn new(name: &str, uri: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            uri: uri.to_string(),
+            secret: None,
+            headers: HashMap::new(),
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn secret(mut self, secret: &str) -> Self {
+        self.secret = Some(secret.to_string());
+        self
+    }
+
+    pub fn headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self, digest: &Digest) -> anyhow::Result<Rendered> {
+        let document = DigestDocument::new(digest);
+
+        Ok(Rendered {
+            text: self.locale.open_pull_requests(document.total),
+            payload: Payload::Webhook(document),
+        })
+    }
+
+    async fn send(&self, rendered: &Rendered) -> anyhow::Result<()> {
+        let Payload::Webhook(document) = &rendered.payload else {
+            return Err(notifier::unexpected_payload(&self.name));
+        };
+
+        let body = serde_json::to_vec(document)?;
+
+        Ok(WebhookApi::post(&self.uri, body, self.secret.as_deref(), &self.headers).await?)
+    }
+}
+
+#[cfg(test)]
+mod webhook_test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::domain::model::change_request::{ChangeRequest, Forge};
+    use crate::domain::model::change_request::fixture::{now, open_pull_request, repository};
+
+    #[test]
+    fn test_render_document() {
+        let digest = Digest::new(
+            vec![open_pull_request(1), ChangeRequest { repo: repository("other"), ..open_pull_request(7) }, open_pull_request(2)],
+            now(),
+        );
+
+        let rendered = WebhookNotifier::new("webhook", "http://localhost").render(&digest).unwrap();
+        let payload = serde_json::json!(rendered.payload);
+
+        assert_eq!(3, payload["total"]);
+        assert_eq!(1, payload["version"]);
+        assert_eq!("2024-07-19T08:09:31Z", payload["generated_at"]);
+        assert_eq!(2, payload["repositories"].as_array().unwrap().len());
+        assert_eq!(
+            serde_json::json!({
+                "number": 1,
+                "title": "Test",
+                "url": "https://github.com/reo0306/gospo/pull/1",
+                "state": "open",
+                "draft": false,
+                "author": { "login": "reo0306", "url": "https://github.com/reo0306" },
+                "created_at": "2024-07-16T20:09:31Z",
+                "age_hours": 60,
+                "age_days": 2,
+                "classification": "awaiting_review",
+                "reviewers": [
+                    { "login": "test", "url": "https://github.com/test", "state": "APPROVED" },
+                    { "login": "test2", "url": "https://github.com/test2", "state": "PENDING" },
+                ]
+            }),
+            payload["repositories"][0]["pull_requests"][0]
+        );
+        assert_eq!(2, payload["repositories"][0]["pull_requests"][1]["number"]);
+        assert_eq!("reo0306/other", payload["repositories"][1]["full_name"]);
+        assert_eq!("3 open pull requests", rendered.text);
+    }
+
+    #[test]
+    fn test_render_document_keys_repositories_by_forge() {
+        let mut gitlab = open_pull_request(3);
+        gitlab.repo.forge = Forge::Gitlab;
+        gitlab.repo.url = "https://gitlab.example.com/api/v4/projects/42".to_string();
+
+        let digest = Digest::new(vec![open_pull_request(1), gitlab, open_pull_request(2)], now());
+
+        let rendered = WebhookNotifier::new("webhook", "http://localhost").locale(Locale::Ja).render(&digest).unwrap();
+        let payload = serde_json::json!(rendered.payload);
+
+        assert_eq!(
+            vec![("github", "reo0306/gospo", 2), ("gitlab", "reo0306/gospo", 1)],
+            payload["repositories"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|repo| (
+                    repo["forge"].as_str().unwrap(),
+                    repo["full_name"].as_str().unwrap(),
+                    repo["pull_requests"].as_array().unwrap().len(),
+                ))
+                .collect::<Vec<(&str, &str, usize)>>()
+        );
+        assert_eq!(Locale::Ja.open_pull_requests(3), rendered.text);
+    }
+
+    #[test]
+    fn test_render_document_matches_schema() {
+        let schema = serde_json::from_str(include_str!("../../schema/digest.v1.json")).unwrap();
+        let validator = jsonschema::JSONSchema::compile(&schema).unwrap();
+
+        let draft = ChangeRequest { repo: repository("other"), draft: true, reviewers: Vec::new(), ..open_pull_request(7) };
+
+        for pulls in [vec![], vec![open_pull_request(1), draft]] {
+            let rendered = WebhookNotifier::new("webhook", "http://localhost").render(&Digest::new(pulls, now())).unwrap();
+
+            let errors = match validator.validate(&serde_json::json!(rendered.payload)) {
+                Ok(()) => Vec::new(),
+                Err(errors) => errors.map(|error| error.to_string()).collect::<Vec<String>>(),
+            };
+
+            assert!(errors.is_empty(), "{}", errors.join("\n"));
+        }
+    }
+
+    #[rstest]
+    // Reference values from `printf '%s' "$body" | openssl dgst -sha256 -hmac "$secret"`.
+    #[case("secret", "{}", "sha256=77325902caca812dc259733aacd046b73817372c777b8d95b402647474516e13")]
+    #[case("key", "The quick brown fox jumps over the lazy dog", "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8")]
+    fn test_sign(#[case] secret: &str, #[case] body: &str, #[case] expected: &str) {
+        assert_eq!(expected, WebhookApi::sign(secret, body.as_bytes()));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_notifier_signs_request() {
+        let mut server = mockito::Server::new_async().await;
+        let notifier = WebhookNotifier::new("webhook", &format!("{}/hook", server.url()))
+            .secret("s3cret")
+            .headers(HashMap::from([("X-Team".to_string(), "platform".to_string())]));
+
+        let rendered = notifier.render(&Digest::new(vec![open_pull_request(1)], now())).unwrap();
+        let body = serde_json::to_vec(&rendered.payload).unwrap();
+
+        let mock = server
+            .mock("POST", "/hook")
+            .match_header("content-type", "application/json")
+            .match_header("x-digest-schema-version", "1")
+            .match_header("x-team", "platform")
+            .match_header("x-signature-256", WebhookApi::sign("s3cret", &body).as_str())
+            .match_body(body)
+            .with_status(204)
+            .create_async()
+            .await;
+
+        assert!(notifier.send(&rendered).await.is_ok());
+
+        mock.assert_async().await;
+    }
+}
@@ -1,2 +1,16 @@
+pub mod azure_devops;
+pub mod bitbucket;
+pub mod change_request;
+pub mod digest;
+pub mod discord;
+pub mod gitea;
 pub mod github;
-pub mod slack;
\ No newline at end of file
+pub mod gitlab;
+pub mod google_chat;
+pub mod line;
+pub mod locale;
+pub mod matrix;
+pub mod mattermost;
+pub mod slack;
+pub mod teams;
+pub mod webhook;
\ No newline at end of file
@@ -0,0 +1,144 @@
+//! Azure DevOps (Services and Server) Git REST API shapes.
+
+use serde::{Serialize, Deserialize};
+use chrono::DateTime;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use crate::domain::model::change_request::{
+    ChangeRequest,
+    Forge,
+    Repository,
+    Reviewer,
+    ReviewVerdict,
+    User,
+};
+
+/// Everything but RFC 3986 unreserved characters, so project and repository
+/// names can't break out of their path segment.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+/// Percent-encodes a project or repository name such as `Web Site` for use
+/// in a URL path.
+pub fn path_segment(name: &str) -> String {
+    utf8_percent_encode(name, PATH_SEGMENT).to_string()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AzureList<T> {
+    pub value: Vec<T>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AzurePullRequest {
+    pub pull_request_id: u32,
+    pub title: String,
+    pub status: String,
+    #[serde(default)]
+    pub is_draft: bool,
+    pub created_by: AzureIdentity,
+    pub creation_date: String,
+    pub repository: AzureRepository,
+    #[serde(default)]
+    pub reviewers: Vec<AzureReviewer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureIdentity {
+    pub unique_name: String,
+    #[serde(default)]
+    pub image_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureReviewer {
+    #[serde(flatten)]
+    pub identity: AzureIdentity,
+    pub vote: i32,
+    /// Set for groups and teams, whose vote only mirrors their members'.
+    #[serde(default)]
+    pub is_container: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AzureRepository {
+    pub name: String,
+    pub url: String,
+    pub project: AzureProject,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AzureProject {
+    pub name: String,
+}
+
+impl From<&AzureIdentity> for User {
+    /// Identities have no profile page; `uniqueName` is usually an email.
+    fn from(identity: &AzureIdentity) -> Self {
+        Self {
+            login: identity.unique_name.to_string(),
+            html_url: if identity.unique_name.contains('@') {
+                format!("mailto:{}", identity.unique_name)
+            } else {
+                String::new()
+            },
+            avatar_url: identity.image_url.clone(),
+        }
+    }
+}
+
+impl AzureReviewer {
+    /// 10 approved and 5 approved with suggestions both approve. -10
+    /// rejected and -5 waiting for author both hand the pull request back
+    /// to its author, which is what changes requested means on the other
+    /// forges, so the digest treats them alike. 0, or any vote the API adds
+    /// later, counts as not reviewed yet.
+    fn verdict(&self) -> ReviewVerdict {
+        match self.vote {
+            10 | 5 => ReviewVerdict::Approved,
+            -10 | -5 => ReviewVerdict::ChangesRequested,
+            _ => ReviewVerdict::Pending,
+        }
+    }
+}
+
+impl AzurePullRequest {
+    pub fn repository(&self) -> Repository {
+        Repository {
+            forge: Forge::AzureDevops,
+            name: self.repository.name.to_string(),
+            full_name: format!("{}/{}", self.repository.project.name, self.repository.name),
+            url: self.repository.url.to_string(),
+        }
+    }
+
+    /// `organization_uri` is the web root the pull request page hangs off,
+    /// e.g. `https://dev.azure.com/contoso`.
+    pub fn change_request(&self, organization_uri: &str) -> Result<ChangeRequest, chrono::ParseError> {
+        let reviewers = self.reviewers
+            .iter()
+            .filter(|reviewer| !reviewer.is_container)
+            .map(|reviewer| Reviewer { user: User::from(&reviewer.identity), verdict: reviewer.verdict() })
+            .collect();
+
+        Ok(ChangeRequest {
+            repo: self.repository(),
+            number: self.pull_request_id,
+            title: self.title.to_string(),
+            html_url: format!(
+                "{}/{}/_git/{}/pullrequest/{}",
+                organization_uri,
+                path_segment(&self.repository.project.name),
+                path_segment(&self.repository.name),
+                self.pull_request_id
+            ),
+            state: self.status.to_string(),
+            draft: self.is_draft,
+            user: User::from(&self.created_by),
+            created_at: DateTime::parse_from_rfc3339(&self.creation_date)?.naive_utc(),
+            reviewers,
+        })
+    }
+}
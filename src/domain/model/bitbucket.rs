@@ -0,0 +1,215 @@
+//! Bitbucket Cloud (REST 2.0) and Bitbucket Data Center (REST 1.0) shapes.
+
+use serde::{Serialize, Deserialize};
+use chrono::DateTime;
+
+use crate::domain::model::change_request::{
+    ChangeRequest,
+    Forge,
+    Repository,
+    Reviewer,
+    ReviewVerdict,
+    User,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloudPage<T> {
+    pub values: Vec<T>,
+    /// Absolute URL of the next page; absent on the last one.
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloudPullRequest {
+    pub id: u32,
+    pub title: String,
+    pub state: String,
+    #[serde(default)]
+    pub draft: bool,
+    pub author: CloudUser,
+    pub created_on: String,
+    pub links: CloudLinks,
+    #[serde(default)]
+    pub participants: Vec<CloudParticipant>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloudUser {
+    pub nickname: String,
+    pub links: CloudLinks,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CloudLinks {
+    #[serde(default)]
+    pub html: Option<Link>,
+    #[serde(default)]
+    pub avatar: Option<Link>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Link {
+    pub href: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloudParticipant {
+    pub user: CloudUser,
+    /// `REVIEWER` or `PARTICIPANT`.
+    pub role: String,
+    /// `approved`, `changes_requested` or `null`.
+    #[serde(default)]
+    pub state: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCenterPage<T> {
+    pub values: Vec<T>,
+    pub is_last_page: bool,
+    #[serde(default)]
+    pub next_page_start: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCenterPullRequest {
+    pub id: u32,
+    pub title: String,
+    pub state: String,
+    #[serde(default)]
+    pub draft: bool,
+    pub author: DataCenterParticipant,
+    #[serde(default)]
+    pub reviewers: Vec<DataCenterParticipant>,
+    #[serde(default)]
+    pub participants: Vec<DataCenterParticipant>,
+    /// Milliseconds since the epoch.
+    pub created_date: i64,
+    pub links: DataCenterLinks,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DataCenterParticipant {
+    pub user: DataCenterUser,
+    /// `APPROVED`, `NEEDS_WORK` or `UNAPPROVED`.
+    pub status: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DataCenterUser {
+    pub name: String,
+    #[serde(default)]
+    pub links: DataCenterLinks,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DataCenterLinks {
+    #[serde(default, rename = "self")]
+    pub self_: Vec<Link>,
+}
+
+impl DataCenterLinks {
+    fn href(&self) -> String {
+        self.self_.first().map(|link| link.href.to_string()).unwrap_or_default()
+    }
+}
+
+impl From<&CloudUser> for User {
+    fn from(user: &CloudUser) -> Self {
+        Self {
+            login: user.nickname.to_string(),
+            html_url: user.links.html.as_ref().map(|link| link.href.to_string()).unwrap_or_default(),
+            avatar_url: user.links.avatar.as_ref().map(|link| link.href.to_string()),
+        }
+    }
+}
+
+impl From<&DataCenterUser> for User {
+    fn from(user: &DataCenterUser) -> Self {
+        Self { login: user.name.to_string(), html_url: user.links.href(), avatar_url: None }
+    }
+}
+
+/// `workspace/slug` for Cloud, `PROJECT/slug` for Data Center.
+pub fn repository(api_uri: &str, full_name: &str, path: &str) -> Repository {
+    Repository {
+        forge: Forge::Bitbucket,
+        name: full_name.rsplit('/').next().unwrap_or(full_name).to_string(),
+        full_name: full_name.to_string(),
+        url: format!("{}/{}", api_uri, path),
+    }
+}
+
+impl CloudPullRequest {
+    /// Reviewers, plus participants who approved or requested changes
+    /// without being asked.
+    pub fn change_request(&self, repo: Repository) -> Result<ChangeRequest, chrono::ParseError> {
+        let reviewers = self.participants
+            .iter()
+            .filter_map(|participant| {
+                let verdict = match participant.state.as_deref() {
+                    Some("approved") => ReviewVerdict::Approved,
+                    Some("changes_requested") => ReviewVerdict::ChangesRequested,
+                    _ if participant.role == "REVIEWER" => ReviewVerdict::Pending,
+                    _ => return None,
+                };
+
+                Some(Reviewer { user: User::from(&participant.user), verdict })
+            })
+            .collect();
+
+        Ok(ChangeRequest {
+            repo,
+            number: self.id,
+            title: self.title.to_string(),
+            html_url: self.links.html.as_ref().map(|link| link.href.to_string()).unwrap_or_default(),
+            state: self.state.to_lowercase(),
+            draft: self.draft,
+            user: User::from(&self.author),
+            created_at: DateTime::parse_from_rfc3339(&self.created_on)?.naive_utc(),
+            reviewers,
+        })
+    }
+}
+
+impl DataCenterParticipant {
+    fn verdict(&self) -> ReviewVerdict {
+        match self.status.as_str() {
+            "APPROVED" => ReviewVerdict::Approved,
+            "NEEDS_WORK" => ReviewVerdict::ChangesRequested,
+            _ => ReviewVerdict::Pending,
+        }
+    }
+}
+
+impl DataCenterPullRequest {
+    /// Reviewers, plus participants who approved or marked "needs work"
+    /// without being asked.
+    pub fn change_request(&self, repo: Repository) -> ChangeRequest {
+        let mut reviewers = self.reviewers
+            .iter()
+            .map(|reviewer| Reviewer { user: User::from(&reviewer.user), verdict: reviewer.verdict() })
+            .collect::<Vec<Reviewer>>();
+
+        reviewers.extend(
+            self.participants
+                .iter()
+                .filter(|participant| participant.verdict() != ReviewVerdict::Pending)
+                .map(|participant| Reviewer { user: User::from(&participant.user), verdict: participant.verdict() })
+        );
+
+        ChangeRequest {
+            repo,
+            number: self.id,
+            title: self.title.to_string(),
+            html_url: self.links.href(),
+            state: self.state.to_lowercase(),
+            draft: self.draft,
+            user: User::from(&self.author.user),
+            created_at: DateTime::from_timestamp_millis(self.created_date).unwrap_or_default().naive_utc(),
+            reviewers,
+        }
+    }
+}
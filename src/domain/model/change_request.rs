@@ -0,0 +1,357 @@
+//! Forge-neutral view of an open change request (pull request, merge
+//! request, ...). Sources map their API shapes into these types; rendering
+//! and the review rules only ever see this module.
+
+use std::fmt;
+use serde::{Serialize, Deserialize};
+use chrono::NaiveDateTime;
+
+use crate::domain::model::locale::{Labels, Locale};
+use crate::domain::text;
+
+/// Longest title any renderer shows before cutting it short.
+pub const MAX_TITLE_CHARS: usize = 200;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Forge {
+    #[default]
+    Github,
+    Gitlab,
+    Gitea,
+    Bitbucket,
+    AzureDevops,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Repository {
+    pub forge: Forge,
+    pub name: String,
+    pub full_name: String,
+    /// API URL of the repository on its forge.
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct User {
+    pub login: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReviewVerdict {
+    #[default]
+    Pending,
+    Commented,
+    ChangesRequested,
+    Approved,
+}
+
+/// Where review of a change request stands, from its reviewers' verdicts.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    /// Nobody has been asked to review.
+    Unassigned,
+    /// At least one requested reviewer has not reviewed yet.
+    AwaitingReview,
+    /// At least one reviewer requested changes.
+    ChangesRequested,
+    /// Every reviewer approved.
+    Approved,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Reviewer {
+    #[serde(flatten)]
+    pub user: User,
+    pub verdict: ReviewVerdict,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChangeRequest {
+    pub repo: Repository,
+    pub number: u32,
+    pub title: String,
+    pub html_url: String,
+    pub state: String,
+    pub draft: bool,
+    pub user: User,
+    pub created_at: NaiveDateTime,
+    /// Requested reviewers with their latest verdict, in request order.
+    pub reviewers: Vec<Reviewer>,
+}
+
+impl fmt::Display for Forge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Github => "GitHub",
+            Self::Gitlab => "GitLab",
+            Self::Gitea => "Gitea",
+            Self::Bitbucket => "Bitbucket",
+            Self::AzureDevops => "Azure DevOps",
+        })
+    }
+}
+
+impl ReviewVerdict {
+    pub fn emoji(self) -> &'static str {
+        match self {
+            Self::Approved => "✅",
+            Self::ChangesRequested => "❌",
+            Self::Commented => "💬",
+            Self::Pending => "⏳",
+        }
+    }
+
+    /// The same marks as `emoji`, as Slack-style `:shortcodes:`.
+    pub fn shortcode(self) -> &'static str {
+        match self {
+            Self::Approved => ":white_check_mark:",
+            Self::ChangesRequested => ":x:",
+            Self::Commented => ":speech_balloon:",
+            Self::Pending => ":hourglass_flowing_sand:",
+        }
+    }
+}
+
+impl ReviewStatus {
+    pub fn of(verdicts: &[ReviewVerdict]) -> Self {
+        if verdicts.is_empty() {
+            Self::Unassigned
+        } else if verdicts.contains(&ReviewVerdict::ChangesRequested) {
+            Self::ChangesRequested
+        } else if verdicts.iter().all(|verdict| *verdict == ReviewVerdict::Approved) {
+            Self::Approved
+        } else {
+            Self::AwaitingReview
+        }
+    }
+}
+
+impl Repository {
+    /// An empty filter matches every repository.
+    pub fn matches(&self, filter: &[String]) -> bool {
+        filter.is_empty() || filter.iter().any(|name| name == &self.full_name || name == &self.name)
+    }
+}
+
+impl ChangeRequest {
+    pub fn text_line(&self) -> TextLine<'_> {
+        TextLine::new(self)
+    }
+
+    pub fn status(&self) -> ReviewStatus {
+        ReviewStatus::of(&self.reviewers.iter().map(|reviewer| reviewer.verdict).collect::<Vec<ReviewVerdict>>())
+    }
+
+    pub fn short_title(&self) -> String {
+        text::truncate(&self.title, MAX_TITLE_CHARS)
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct TextLine<'a> {
+    pub repo: &'a Repository,
+    pub pull: &'a ChangeRequest,
+    pub locale: Locale,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TextLineView<'a> {
+    #[serde(flatten)]
+    pub line: &'a TextLine<'a>,
+    pub created_at: String,
+    pub age: Age,
+    pub age_text: String,
+    pub labels: Labels,
+    pub unapproved_reviewers: Vec<String>,
+    pub reviewer_states: Vec<ReviewerState>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Age {
+    pub days: i64,
+    pub hours: i64,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct ReviewerState {
+    pub login: String,
+    pub html_url: String,
+    pub state: ReviewVerdict,
+}
+
+impl<'a> TextLine<'a> {
+    pub fn new(pull: &'a ChangeRequest) -> Self {
+        Self { repo: &pull.repo, pull, locale: Locale::default() }
+    }
+
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn unapproved_reviewer_logins(&self) -> Vec<String> {
+        self.pull.reviewers
+            .iter()
+            .filter(|reviewer| reviewer.verdict != ReviewVerdict::Approved)
+            .map(|reviewer| reviewer.user.login.to_string())
+            .collect()
+    }
+
+    pub fn reviewer_states(&self) -> Vec<(&User, ReviewVerdict)> {
+        self.pull.reviewers
+            .iter()
+            .map(|reviewer| (&reviewer.user, reviewer.verdict))
+            .collect()
+    }
+
+    pub fn created_at(&self) -> String {
+        self.locale.format_datetime(&self.pull.created_at)
+    }
+
+    pub fn age(&self, now: NaiveDateTime) -> Age {
+        let elapsed = now - self.pull.created_at;
+
+        Age { days: elapsed.num_days(), hours: elapsed.num_hours() }
+    }
+
+    pub fn view(&self, now: NaiveDateTime) -> TextLineView<'_> {
+        let age = self.age(now);
+
+        TextLineView {
+            line: self,
+            created_at: self.created_at(),
+            age_text: self.locale.relative_age(&age),
+            age,
+            labels: self.locale.labels(),
+            unapproved_reviewers: self.unapproved_reviewer_logins(),
+            reviewer_states: self.reviewer_states()
+                .into_iter()
+                .map(|(reviewer, state)| ReviewerState {
+                    login: reviewer.login.to_string(),
+                    html_url: reviewer.html_url.to_string(),
+                    state,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Change requests for the renderers' tests, built directly rather than
+/// through any forge's API shapes.
+#[cfg(test)]
+pub(crate) mod fixture {
+    use super::*;
+
+    pub fn user(login: &str) -> User {
+        User { login: login.to_string(), html_url: format!("https://github.com/{}", login), avatar_url: None }
+    }
+
+    pub fn reviewer(login: &str, verdict: ReviewVerdict) -> Reviewer {
+        Reviewer { user: user(login), verdict }
+    }
+
+    /// When the digest is generated: 2024-07-19T08:09:31, 60 hours after
+    /// every fixture was opened.
+    pub fn now() -> NaiveDateTime {
+        NaiveDateTime::parse_from_str("2024-07-19T08:09:31Z", "%Y-%m-%dT%H:%M:%SZ").unwrap()
+    }
+
+    pub fn repository(name: &str) -> Repository {
+        Repository {
+            forge: Forge::Github,
+            name: name.to_string(),
+            full_name: format!("reo0306/{}", name),
+            url: format!("https://api.github.com/repos/reo0306/{}", name),
+        }
+    }
+
+    /// `reo0306/gospo#<number>` titled "Test" by reo0306, approved by `test`
+    /// and still waiting on `test2`.
+    pub fn open_pull_request(number: u32) -> ChangeRequest {
+        ChangeRequest {
+            repo: repository("gospo"),
+            number,
+            title: "Test".to_string(),
+            html_url: format!("https://github.com/reo0306/gospo/pull/{}", number),
+            state: "open".to_string(),
+            draft: false,
+            user: user("reo0306"),
+            created_at: NaiveDateTime::parse_from_str("2024-07-16T20:09:31Z", "%Y-%m-%dT%H:%M:%SZ").unwrap(),
+            reviewers: vec![reviewer("test", ReviewVerdict::Approved), reviewer("test2", ReviewVerdict::Pending)],
+        }
+    }
+
+    /// `open_pull_request(1)` opened at `created_at`, with one reviewer
+    /// (`test1`, `test2`, ...) per verdict.
+    pub fn pull_request_opened_at(created_at: &str, verdicts: Vec<ReviewVerdict>) -> ChangeRequest {
+        ChangeRequest {
+            created_at: NaiveDateTime::parse_from_str(created_at, "%Y-%m-%dT%H:%M:%SZ").unwrap(),
+            reviewers: verdicts
+                .into_iter()
+                .enumerate()
+                .map(|(i, verdict)| reviewer(&format!("test{}", i + 1), verdict))
+                .collect(),
+            ..open_pull_request(1)
+        }
+    }
+
+    /// `open_pull_request(1)` under another title.
+    pub fn open_pull_request_titled(title: &str) -> ChangeRequest {
+        ChangeRequest { title: title.to_string(), ..open_pull_request(1) }
+    }
+}
+
+#[cfg(test)]
+mod change_request_test {
+    use rstest::rstest;
+
+    use super::*;
+    use super::fixture::{open_pull_request, reviewer};
+
+    #[test]
+    fn test_unapproved_reviewer_logins() {
+        let pull = ChangeRequest {
+            reviewers: vec![
+                reviewer("test", ReviewVerdict::Approved),
+                reviewer("test2", ReviewVerdict::Pending),
+                reviewer("test3", ReviewVerdict::ChangesRequested),
+                reviewer("test4", ReviewVerdict::Commented),
+            ],
+            ..open_pull_request(1)
+        };
+
+        assert_eq!(vec!["test2", "test3", "test4"], pull.text_line().unapproved_reviewer_logins());
+    }
+
+    #[rstest]
+    #[case(vec![], ReviewStatus::Unassigned)]
+    #[case(vec![ReviewVerdict::Pending], ReviewStatus::AwaitingReview)]
+    #[case(vec![ReviewVerdict::Approved, ReviewVerdict::Commented], ReviewStatus::AwaitingReview)]
+    #[case(vec![ReviewVerdict::Approved, ReviewVerdict::ChangesRequested], ReviewStatus::ChangesRequested)]
+    #[case(vec![ReviewVerdict::Approved, ReviewVerdict::Approved], ReviewStatus::Approved)]
+    fn test_status(#[case] verdicts: Vec<ReviewVerdict>, #[case] expected: ReviewStatus) {
+        let pull = ChangeRequest {
+            reviewers: verdicts.into_iter().enumerate().map(|(i, verdict)| reviewer(&format!("test{}", i), verdict)).collect(),
+            ..open_pull_request(1)
+        };
+
+        assert_eq!(expected, pull.status());
+    }
+
+    #[rstest]
+    #[case(vec![], true)]
+    #[case(vec!["gospo"], true)]
+    #[case(vec!["reo0306/gospo"], true)]
+    #[case(vec!["other", "reo0306/other"], false)]
+    fn test_repository_matches(#[case] filter: Vec<&str>, #[case] expected: bool) {
+        let filter = filter.into_iter().map(String::from).collect::<Vec<String>>();
+
+        assert_eq!(expected, open_pull_request(1).repo.matches(&filter));
+    }
+}
@@ -0,0 +1,90 @@
+use serde::Serialize;
+use chrono::{NaiveDate, NaiveDateTime};
+use chrono_tz::Tz;
+
+use crate::domain::model::{change_request::ChangeRequest, locale::Locale};
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Digest {
+    pub pulls: Vec<ChangeRequest>,
+    /// UTC.
+    pub generated_at: NaiveDateTime,
+    /// Zone of the job the digest is for; its calendar decides `local_date`.
+    pub timezone: Tz,
+}
+
+impl Digest {
+    pub fn new(pulls: Vec<ChangeRequest>, generated_at: NaiveDateTime) -> Self {
+        Self { pulls, generated_at, timezone: Tz::UTC }
+    }
+
+    pub fn timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// The day the digest was generated on in `timezone`.
+    pub fn local_date(&self) -> NaiveDate {
+        self.generated_at.and_utc().with_timezone(&self.timezone).date_naive()
+    }
+
+    /// Plain-text rendering for notification previews and fallbacks: the
+    /// count, then `title - repo#number url` per pull request.
+    pub fn text(&self, locale: Locale) -> String {
+        let mut lines = vec![locale.open_pull_requests(self.pulls.len())];
+
+        lines.extend(self.pulls.iter().map(|pull| {
+            format!("{} - {}#{} {}", pull.title, pull.repo.full_name, pull.number, pull.html_url)
+        }));
+
+        lines.join("\n")
+    }
+
+    /// Keeps ready pull requests open for at least `hours` that nobody
+    /// reviews or that still wait on a reviewer.
+    pub fn overdue(mut self, hours: i64) -> Self {
+        let now = self.generated_at;
+
+        self.pulls.retain(|pull| {
+            let text_line = pull.text_line();
+
+            !pull.draft
+                && text_line.age(now).hours >= hours
+                && (pull.reviewers.is_empty() || !text_line.unapproved_reviewer_logins().is_empty())
+        });
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod digest_test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::domain::model::change_request::{
+        ReviewVerdict,
+        fixture::{now, pull_request_opened_at},
+    };
+
+    #[test]
+    fn test_text() {
+        let digest = Digest::new(vec![pull_request_opened_at("2024-07-16T20:09:31Z", Vec::new())], now());
+
+        assert_eq!("1 open pull request\nTest - reo0306/gospo#1 https://github.com/reo0306/gospo/pull/1", digest.text(Locale::En));
+    }
+
+    #[rstest]
+    #[case("2024-07-16T20:09:31Z", false, vec![ReviewVerdict::Pending], true)]
+    #[case("2024-07-16T20:09:31Z", false, vec![], true)]
+    #[case("2024-07-16T20:09:31Z", false, vec![ReviewVerdict::Approved], false)]
+    #[case("2024-07-16T20:09:31Z", true, vec![ReviewVerdict::Pending], false)]
+    #[case("2024-07-19T00:09:31Z", false, vec![ReviewVerdict::Pending], false)]
+    fn test_overdue(#[case] created_at: &str, #[case] draft: bool, #[case] verdicts: Vec<ReviewVerdict>, #[case] expected: bool) {
+        let pull = ChangeRequest { draft, ..pull_request_opened_at(created_at, verdicts) };
+
+        let digest = Digest::new(vec![pull], now()).overdue(24);
+
+        assert_eq!(expected, !digest.pulls.is_empty());
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct Discord {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub embeds: Vec<Embed>,
+    pub allowed_mentions: AllowedMentions,
+}
+
+/// An empty `parse` list keeps `@everyone` or `<@id>` in PR data from pinging anyone.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
+pub struct AllowedMentions {
+    pub parse: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct Embed {
+    pub title: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub color: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<EmbedField>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub footer: Option<EmbedFooter>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct EmbedField {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct EmbedFooter {
+    pub text: String,
+}
+
+impl Discord {
+    pub fn new(content: Option<String>, embeds: Vec<Embed>) -> Self {
+        Self { content, embeds, allowed_mentions: AllowedMentions::default() }
+    }
+}
+
+impl Embed {
+    /// Characters Discord counts towards the 6000 per-message embed total.
+    pub fn chars(&self) -> usize {
+        self.title.chars().count()
+            + self.description.chars().count()
+            + self.fields.iter().map(|field| field.name.chars().count() + field.value.chars().count()).sum::<usize>()
+            + self.footer.as_ref().map(|footer| footer.text.chars().count()).unwrap_or(0)
+    }
+}
+
+impl EmbedField {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self { name: name.to_string(), value: value.to_string(), inline: true }
+    }
+}
@@ -0,0 +1,121 @@
+//! Gitea (and Forgejo, which keeps the same API) shapes.
+
+use serde::{Serialize, Deserialize};
+use chrono::DateTime;
+
+use crate::domain::model::change_request::{
+    ChangeRequest,
+    Forge,
+    Repository,
+    Reviewer,
+    ReviewVerdict,
+    User,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GiteaRepository {
+    pub name: String,
+    pub full_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GiteaPullRequest {
+    pub number: u32,
+    pub title: String,
+    pub html_url: String,
+    pub state: String,
+    #[serde(default)]
+    pub draft: bool,
+    pub user: GiteaUser,
+    pub created_at: String,
+    // `null` rather than `[]` when nobody was asked.
+    #[serde(default)]
+    pub requested_reviewers: Option<Vec<GiteaUser>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GiteaUser {
+    pub login: String,
+    #[serde(default)]
+    pub html_url: String,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GiteaReview {
+    /// `None` for reviews requested from a team.
+    #[serde(default)]
+    pub user: Option<GiteaUser>,
+    pub state: String,
+    #[serde(default)]
+    pub dismissed: bool,
+}
+
+impl From<&GiteaUser> for User {
+    fn from(user: &GiteaUser) -> Self {
+        Self {
+            login: user.login.to_string(),
+            html_url: user.html_url.to_string(),
+            avatar_url: user.avatar_url.clone(),
+        }
+    }
+}
+
+impl GiteaRepository {
+    pub fn repository(&self, api_uri: &str) -> Repository {
+        Repository {
+            forge: Forge::Gitea,
+            name: self.name.to_string(),
+            full_name: self.full_name.to_string(),
+            url: format!("{}/repos/{}", api_uri, self.full_name),
+        }
+    }
+}
+
+impl GiteaReview {
+    /// `PENDING` (unsubmitted) and `REQUEST_REVIEW` entries carry no verdict.
+    fn verdict(&self) -> Option<ReviewVerdict> {
+        match self.state.as_str() {
+            "APPROVED" => Some(ReviewVerdict::Approved),
+            "REQUEST_CHANGES" => Some(ReviewVerdict::ChangesRequested),
+            "COMMENT" => Some(ReviewVerdict::Commented),
+            _ => None,
+        }
+    }
+}
+
+impl GiteaPullRequest {
+    /// Requested reviewers plus everyone who submitted a review (Gitea drops
+    /// the request once it is answered), each with their latest verdict.
+    pub fn change_request(&self, repo: Repository, reviews: &[GiteaReview]) -> Result<ChangeRequest, chrono::ParseError> {
+        let mut reviewers = self.requested_reviewers
+            .iter()
+            .flatten()
+            .map(|user| Reviewer { user: user.into(), verdict: ReviewVerdict::Pending })
+            .collect::<Vec<Reviewer>>();
+
+        for review in reviews.iter().filter(|review| !review.dismissed) {
+            let (Some(user), Some(verdict)) = (&review.user, review.verdict()) else {
+                continue;
+            };
+
+            match reviewers.iter_mut().find(|reviewer| reviewer.user.login == user.login) {
+                Some(reviewer) => reviewer.verdict = verdict,
+                None => reviewers.push(Reviewer { user: user.into(), verdict }),
+            }
+        }
+
+        Ok(ChangeRequest {
+            repo,
+            number: self.number,
+            title: self.title.to_string(),
+            html_url: self.html_url.to_string(),
+            state: self.state.to_string(),
+            draft: self.draft,
+            user: User::from(&self.user),
+            created_at: DateTime::parse_from_rfc3339(&self.created_at)?.naive_utc(),
+            reviewers,
+        })
+    }
+}
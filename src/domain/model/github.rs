@@ -1,14 +1,18 @@
+//! GitHub REST API shapes, mapped into `change_request` for the digest.
+
 use serde::{Serialize, Deserialize};
 use chrono::NaiveDateTime;
 
-#[derive(Serialize, Deserialize, Debug)]
+use crate::domain::model::change_request::{self, ChangeRequest, Forge, Reviewer, ReviewVerdict};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Repository {
     pub name: String,
     pub full_name: String,
     pub url: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PullRequest {
     pub html_url: String,
     pub number: u32,
@@ -16,6 +20,8 @@ pub struct PullRequest {
     pub title: String,
     pub user: User,
     pub created_at: String,
+    #[serde(default)]
+    pub draft: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -33,73 +39,94 @@ pub struct Review {
 pub struct User {
     pub login: String,
     pub html_url: String,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Installation {
+    pub id: u64,
+    pub account: User,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstallationToken {
+    pub token: String,
+    pub expires_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstallationRepositories {
+    pub repositories: Vec<Repository>,
 }
 
-#[derive(Serialize, Debug)]
-pub struct TextLine<'a> {
-    pub repo: &'a Repository,
-    pub pull: &'a PullRequest,
+#[derive(Serialize, Debug, Clone)]
+pub struct OpenPullRequest {
+    pub repo: Repository,
+    pub pull: PullRequest,
     pub reviewers: Reviewers,
     pub reviews: Vec<Review>,
 }
 
-impl<'a> TextLine<'a> {
-    pub fn new(repo: &'a Repository, pull: &'a PullRequest, reviewers: Reviewers, reviews: Vec<Review>) -> Self {
-        Self { repo, pull, reviewers, reviews }
+impl OpenPullRequest {
+    pub fn new(repo: &Repository, pull: PullRequest, reviewers: Reviewers, reviews: Vec<Review>) -> Self {
+        Self { repo: repo.clone(), pull, reviewers, reviews }
     }
+}
 
-    pub fn title(&self) -> String {
-        format!(
-            "*{} - <{}|{}#{}>*",
-            &self.pull.title,
-            &self.pull.html_url,
-            &self.repo.full_name,
-            &self.pull.number
-        )
+impl From<User> for change_request::User {
+    fn from(user: User) -> Self {
+        Self { login: user.login, html_url: user.html_url, avatar_url: user.avatar_url }
     }
+}
 
-    pub fn unapproved_reviewers(&self) -> String {
-        let mut users: Vec<String> = Vec::new();
+impl From<Repository> for change_request::Repository {
+    fn from(repo: Repository) -> Self {
+        Self { forge: Forge::Github, name: repo.name, full_name: repo.full_name, url: repo.url }
+    }
+}
 
-        let _ = &self.reviewers.users.clone()
-            .into_iter()
-            .for_each(|reviewer| {
-                if &self.reviews.len() == &0_usize {
-                    users.push((&reviewer.login).to_string());
-                }
-
-                let _ = &self.reviews.clone()
-                    .into_iter()
-                    .filter(|review| {
-                        &review.user.login.len() > &0_usize && &reviewer.login == &review.user.login && review.state != "APPROVED".to_string()
-                    })
-                    .for_each(|_| {
-                        users.push((&reviewer.login).to_string());
-                    });
-            });
-
-        if users.len() == 0 {
-            format!("")
-        } else {
-            format!("unapproved reviewers - {}", users.join(" "))
+impl From<&str> for ReviewVerdict {
+    fn from(state: &str) -> Self {
+        match state {
+            "APPROVED" => Self::Approved,
+            "CHANGES_REQUESTED" => Self::ChangesRequested,
+            "COMMENTED" => Self::Commented,
+            _ => Self::Pending,
         }
     }
+}
 
-    pub fn state(&self) -> String {
-        format!(
-            "*{}* - Created by <{}|{}> on {}",
-            &self.pull.state,
-            &self.pull.user.html_url,
-            &self.pull.user.login,
-            NaiveDateTime::parse_from_str(&self.pull.created_at, "%Y-%m-%dT%H:%M:%SZ").unwrap(),
-        )
-    }
+impl TryFrom<OpenPullRequest> for ChangeRequest {
+    type Error = chrono::ParseError;
+
+    /// Each requested reviewer gets the state of their latest review.
+    fn try_from(open_pull: OpenPullRequest) -> Result<Self, Self::Error> {
+        let OpenPullRequest { repo, pull, reviewers, reviews } = open_pull;
+
+        let reviewers = reviewers.users
+            .into_iter()
+            .map(|reviewer| {
+                let verdict = reviews
+                    .iter()
+                    .rev()
+                    .find(|review| review.user.login == reviewer.login)
+                    .map_or(ReviewVerdict::Pending, |review| ReviewVerdict::from(review.state.as_str()));
+
+                Reviewer { user: reviewer.into(), verdict }
+            })
+            .collect();
 
-    pub fn message(&self) -> String {
-        [
-            self.title(),
-            self.unapproved_reviewers(),
-            self.state()
-        ].join("\n")
+        Ok(Self {
+            repo: repo.into(),
+            number: pull.number,
+            title: pull.title,
+            html_url: pull.html_url,
+            state: pull.state,
+            draft: pull.draft,
+            user: pull.user.into(),
+            created_at: NaiveDateTime::parse_from_str(&pull.created_at, "%Y-%m-%dT%H:%M:%SZ")?,
+            reviewers,
+        })
     }
-}
\ No newline at end of file
+}
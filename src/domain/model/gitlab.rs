@@ -0,0 +1,113 @@
+use serde::{Serialize, Deserialize};
+use chrono::DateTime;
+
+use crate::domain::model::change_request::{
+    ChangeRequest,
+    Forge,
+    Repository,
+    Reviewer,
+    ReviewVerdict,
+    User,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeRequest {
+    pub id: u64,
+    pub iid: u32,
+    pub project_id: u64,
+    pub title: String,
+    pub web_url: String,
+    pub state: String,
+    #[serde(default)]
+    pub draft: bool,
+    pub author: GitlabUser,
+    #[serde(default)]
+    pub reviewers: Vec<GitlabUser>,
+    pub created_at: String,
+    pub references: References,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct References {
+    /// `group/project!iid`
+    pub full: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GitlabUser {
+    pub username: String,
+    pub web_url: String,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Approvals {
+    #[serde(default)]
+    pub approved_by: Vec<Approver>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Approver {
+    pub user: GitlabUser,
+}
+
+impl From<&GitlabUser> for User {
+    fn from(user: &GitlabUser) -> Self {
+        Self {
+            login: user.username.to_string(),
+            html_url: user.web_url.to_string(),
+            avatar_url: user.avatar_url.clone(),
+        }
+    }
+}
+
+impl MergeRequest {
+    pub fn repository(&self, api_uri: &str) -> Repository {
+        let full_name = self.references.full
+            .rsplit_once('!')
+            .map_or(self.references.full.as_str(), |(path, _)| path);
+
+        Repository {
+            forge: Forge::Gitlab,
+            name: full_name.rsplit('/').next().unwrap_or(full_name).to_string(),
+            full_name: full_name.to_string(),
+            url: format!("{}/projects/{}", api_uri, self.project_id),
+        }
+    }
+
+    /// Approvers count as `Approved`; those who approved without being
+    /// asked are added to the reviewers.
+    pub fn change_request(&self, api_uri: &str, approvals: &Approvals) -> Result<ChangeRequest, chrono::ParseError> {
+        let approvers = approvals.approved_by.iter().map(|approver| User::from(&approver.user)).collect::<Vec<User>>();
+
+        let mut reviewers = self.reviewers
+            .iter()
+            .map(|reviewer| {
+                let user = User::from(reviewer);
+                let verdict = if approvers.iter().any(|approver| approver.login == user.login) { ReviewVerdict::Approved } else { ReviewVerdict::Pending };
+
+                Reviewer { user, verdict }
+            })
+            .collect::<Vec<Reviewer>>();
+
+        for approver in approvers {
+            if !reviewers.iter().any(|reviewer| reviewer.user.login == approver.login) {
+                reviewers.push(Reviewer { user: approver, verdict: ReviewVerdict::Approved });
+            }
+        }
+
+        Ok(ChangeRequest {
+            repo: self.repository(api_uri),
+            number: self.iid,
+            title: self.title.to_string(),
+            html_url: self.web_url.to_string(),
+            state: if self.state == "opened" { "open".to_string() } else { self.state.to_string() },
+            draft: self.draft,
+            user: User::from(&self.author),
+            // `2024-07-16T20:09:31.000Z`, or with the instance's offset.
+            created_at: DateTime::parse_from_rfc3339(&self.created_at)?.naive_utc(),
+            reviewers,
+        })
+    }
+}
@@ -0,0 +1,105 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GoogleChat {
+    pub text: String,
+    #[serde(rename = "cardsV2")]
+    pub cards_v2: Vec<CardWithId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Thread {
+    pub thread_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CardWithId {
+    pub card_id: String,
+    pub card: Card,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Card {
+    pub header: CardHeader,
+    pub sections: Vec<Section>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CardHeader {
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Section {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    pub widgets: Vec<Widget>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum Widget {
+    TextParagraph {
+        text: String,
+    },
+    DecoratedText {
+        #[serde(rename = "topLabel")]
+        top_label: String,
+        text: String,
+    },
+    ButtonList {
+        buttons: Vec<Button>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Button {
+    pub text: String,
+    pub on_click: OnClick,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OnClick {
+    pub open_link: OpenLink,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct OpenLink {
+    pub url: String,
+}
+
+impl Section {
+    pub fn new(header: Option<String>, widgets: Vec<Widget>) -> Self {
+        Self { header, widgets }
+    }
+}
+
+impl Widget {
+    pub fn paragraph(text: &str) -> Self {
+        Self::TextParagraph { text: text.to_string() }
+    }
+
+    pub fn decorated(top_label: &str, text: &str) -> Self {
+        Self::DecoratedText { top_label: top_label.to_string(), text: text.to_string() }
+    }
+
+    pub fn link_button(text: &str, url: &str) -> Self {
+        Self::ButtonList {
+            buttons: vec![
+                Button {
+                    text: text.to_string(),
+                    on_click: OnClick { open_link: OpenLink { url: url.to_string() } },
+                }
+            ],
+        }
+    }
+}
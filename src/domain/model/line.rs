@@ -0,0 +1,134 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PushMessage {
+    pub to: String,
+    pub messages: Vec<Message>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Message {
+    #[serde(rename_all = "camelCase")]
+    Flex {
+        alt_text: String,
+        contents: Carousel,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", rename = "carousel")]
+pub struct Carousel {
+    pub contents: Vec<Bubble>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename = "bubble")]
+pub struct Bubble {
+    pub body: FlexBox,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub footer: Option<FlexBox>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename = "box")]
+pub struct FlexBox {
+    pub layout: String,
+    pub contents: Vec<Component>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spacing: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Component {
+    Text(Text),
+    Button {
+        action: Action,
+        style: String,
+    },
+    Separator,
+    // `FlexBox` carries its own `"type": "box"` tag.
+    #[serde(untagged)]
+    Box(FlexBox),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Text {
+    pub text: String,
+    pub wrap: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flex: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Action {
+    Uri {
+        label: String,
+        uri: String,
+    },
+}
+
+impl FlexBox {
+    pub fn vertical(contents: Vec<Component>) -> Self {
+        Self { layout: "vertical".to_string(), contents, spacing: Some("sm".to_string()) }
+    }
+
+    pub fn baseline(contents: Vec<Component>) -> Self {
+        Self { layout: "baseline".to_string(), contents, spacing: Some("sm".to_string()) }
+    }
+}
+
+impl Text {
+    pub fn new(text: &str) -> Self {
+        Self { text: text.to_string(), wrap: true, weight: None, size: None, color: None, flex: None }
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.weight = Some("bold".to_string());
+        self
+    }
+
+    pub fn size(mut self, size: &str) -> Self {
+        self.size = Some(size.to_string());
+        self
+    }
+
+    pub fn color(mut self, color: &str) -> Self {
+        self.color = Some(color.to_string());
+        self
+    }
+
+    pub fn flex(mut self, flex: u32) -> Self {
+        self.flex = Some(flex);
+        self
+    }
+}
+
+impl Component {
+    pub fn link_button(label: &str, uri: &str) -> Self {
+        Self::Button {
+            action: Action::Uri { label: label.to_string(), uri: uri.to_string() },
+            style: "link".to_string(),
+        }
+    }
+}
+
+impl From<Text> for Component {
+    fn from(text: Text) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<FlexBox> for Component {
+    fn from(flex_box: FlexBox) -> Self {
+        Self::Box(flex_box)
+    }
+}
@@ -0,0 +1,151 @@
+use serde::{Serialize, Deserialize};
+use chrono::NaiveDateTime;
+
+use crate::domain::model::change_request::Age;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Label {
+    OpenPullRequest,
+    UnapprovedReviewers,
+    Status,
+    Reviewers,
+    NoReviewers,
+    OpenButton,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Labels {
+    pub open_pull_request: &'static str,
+    pub unapproved_reviewers: &'static str,
+    pub status: &'static str,
+    pub reviewers: &'static str,
+    pub no_reviewers: &'static str,
+    pub open_button: &'static str,
+}
+
+impl Locale {
+    pub fn label(&self, label: Label) -> &'static str {
+        match (self, label) {
+            (Locale::En, Label::OpenPullRequest) => "Open Pull Request",
+            (Locale::En, Label::UnapprovedReviewers) => "unapproved reviewers",
+            (Locale::En, Label::Status) => "Status",
+            (Locale::En, Label::Reviewers) => "Reviewers",
+            (Locale::En, Label::NoReviewers) => "none",
+            (Locale::En, Label::OpenButton) => "Open PR",
+            (Locale::Ja, Label::OpenPullRequest) => "オープン中のプルリクエスト",
+            (Locale::Ja, Label::UnapprovedReviewers) => "未承認のレビュアー",
+            (Locale::Ja, Label::Status) => "ステータス",
+            (Locale::Ja, Label::Reviewers) => "レビュアー",
+            (Locale::Ja, Label::NoReviewers) => "なし",
+            (Locale::Ja, Label::OpenButton) => "PRを開く",
+        }
+    }
+
+    pub fn labels(&self) -> Labels {
+        Labels {
+            open_pull_request: self.label(Label::OpenPullRequest),
+            unapproved_reviewers: self.label(Label::UnapprovedReviewers),
+            status: self.label(Label::Status),
+            reviewers: self.label(Label::Reviewers),
+            no_reviewers: self.label(Label::NoReviewers),
+            open_button: self.label(Label::OpenButton),
+        }
+    }
+
+    pub fn created_by(&self, author: &str, date: &str) -> String {
+        match self {
+            Locale::En => format!("Created by {} on {}", author, date),
+            Locale::Ja => format!("{} が {} に作成", author, date),
+        }
+    }
+
+    pub fn format_datetime(&self, datetime: &NaiveDateTime) -> String {
+        match self {
+            Locale::En => datetime.to_string(),
+            Locale::Ja => datetime.format("%Y年%m月%d日 %H:%M").to_string(),
+        }
+    }
+
+    pub fn relative_age(&self, age: &Age) -> String {
+        match (self, age.days, age.hours) {
+            (Locale::En, 0, 0) => "just now".to_string(),
+            (Locale::En, 0, 1) => "1 hour ago".to_string(),
+            (Locale::En, 0, hours) => format!("{} hours ago", hours),
+            (Locale::En, 1, _) => "1 day ago".to_string(),
+            (Locale::En, days, _) => format!("{} days ago", days),
+            (Locale::Ja, 0, 0) => "たった今".to_string(),
+            (Locale::Ja, 0, hours) => format!("{}時間前", hours),
+            (Locale::Ja, days, _) => format!("{}日前", days),
+        }
+    }
+
+    pub fn open_pull_requests(&self, count: usize) -> String {
+        match (self, count) {
+            (Locale::En, 0) => "No open pull requests".to_string(),
+            (Locale::En, 1) => "1 open pull request".to_string(),
+            (Locale::En, count) => format!("{} open pull requests", count),
+            (Locale::Ja, 0) => "オープン中のプルリクエストはありません".to_string(),
+            (Locale::Ja, count) => format!("オープン中のプルリクエスト {} 件", count),
+        }
+    }
+
+    pub fn more(&self, count: usize) -> String {
+        match self {
+            Locale::En => format!("and {} more", count),
+            Locale::Ja => format!("他 {} 件", count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod locale_test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(Locale::En, 0, 0, "just now")]
+    #[case(Locale::En, 0, 1, "1 hour ago")]
+    #[case(Locale::En, 0, 5, "5 hours ago")]
+    #[case(Locale::En, 1, 30, "1 day ago")]
+    #[case(Locale::En, 3, 80, "3 days ago")]
+    #[case(Locale::Ja, 0, 0, "たった今")]
+    #[case(Locale::Ja, 0, 1, "1時間前")]
+    #[case(Locale::Ja, 3, 80, "3日前")]
+    fn test_relative_age(#[case] locale: Locale, #[case] days: i64, #[case] hours: i64, #[case] expected: &str) {
+        assert_eq!(expected, locale.relative_age(&Age { days, hours }));
+    }
+
+    #[rstest]
+    #[case(Locale::En, 0, "No open pull requests")]
+    #[case(Locale::En, 1, "1 open pull request")]
+    #[case(Locale::En, 2, "2 open pull requests")]
+    #[case(Locale::Ja, 0, "オープン中のプルリクエストはありません")]
+    #[case(Locale::Ja, 2, "オープン中のプルリクエスト 2 件")]
+    fn test_open_pull_requests(#[case] locale: Locale, #[case] count: usize, #[case] expected: &str) {
+        assert_eq!(expected, locale.open_pull_requests(count));
+    }
+
+    #[rstest]
+    #[case(Locale::En, "2024-07-16 20:09:31")]
+    #[case(Locale::Ja, "2024年07月16日 20:09")]
+    fn test_format_datetime(#[case] locale: Locale, #[case] expected: &str) {
+        let datetime = NaiveDateTime::parse_from_str("2024-07-16T20:09:31Z", "%Y-%m-%dT%H:%M:%SZ").unwrap();
+
+        assert_eq!(expected, locale.format_datetime(&datetime));
+    }
+
+    #[test]
+    fn test_deserialize() {
+        assert_eq!(Locale::Ja, serde_json::from_str::<Locale>("\"ja\"").unwrap());
+        assert!(serde_json::from_str::<Locale>("\"fr\"").is_err());
+    }
+}
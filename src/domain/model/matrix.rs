@@ -0,0 +1,52 @@
+use serde::{Serialize, Deserialize};
+
+pub const HTML_FORMAT: &str = "org.matrix.custom.html";
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RoomMessage {
+    pub msgtype: String,
+    pub body: String,
+    pub format: String,
+    pub formatted_body: String,
+    #[serde(rename = "m.new_content", default, skip_serializing_if = "Option::is_none")]
+    pub new_content: Option<Box<RoomMessage>>,
+    #[serde(rename = "m.relates_to", default, skip_serializing_if = "Option::is_none")]
+    pub relates_to: Option<RelatesTo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RelatesTo {
+    pub rel_type: String,
+    pub event_id: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SendResponse {
+    pub event_id: String,
+}
+
+impl RoomMessage {
+    pub fn new(body: &str, formatted_body: &str) -> Self {
+        Self {
+            msgtype: "m.notice".to_string(),
+            body: body.to_string(),
+            format: HTML_FORMAT.to_string(),
+            formatted_body: formatted_body.to_string(),
+            new_content: None,
+            relates_to: None,
+        }
+    }
+
+    /// Wraps this message as an `m.replace` edit of `event_id`; clients without
+    /// edit support show the `* `-prefixed fallback.
+    pub fn replacing(self, event_id: &str) -> Self {
+        Self {
+            msgtype: self.msgtype.to_string(),
+            body: format!("* {}", self.body),
+            format: HTML_FORMAT.to_string(),
+            formatted_body: format!("* {}", self.formatted_body),
+            new_content: Some(Box::new(self)),
+            relates_to: Some(RelatesTo { rel_type: "m.replace".to_string(), event_id: event_id.to_string() }),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Mattermost {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Attachment {
+    pub fallback: String,
+    pub color: String,
+    pub title: String,
+    pub title_link: String,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Field>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Field {
+    pub title: String,
+    pub value: String,
+    pub short: bool,
+}
+
+impl Field {
+    pub fn new(title: &str, value: &str) -> Self {
+        Self { title: title.to_string(), value: value.to_string(), short: true }
+    }
+}
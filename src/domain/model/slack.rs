@@ -2,17 +2,228 @@ use serde::{Serialize, Deserialize};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Slack {
-    pub blocks: Vec<Message>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    pub blocks: Vec<Block>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
-pub struct Message {
-    pub r#type: String,
-    pub text: Text,
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Block {
+    Header(Header),
+    Section(Section),
+    Context(Context),
+    Divider,
+    Actions(Actions),
+    RichText(RichText),
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct Text {
     pub r#type: String,
     pub text: String,
 }
+
+impl Text {
+    pub fn mrkdwn(text: &str) -> Self {
+        Self { r#type: "mrkdwn".to_string(), text: text.to_string() }
+    }
+
+    pub fn plain(text: &str) -> Self {
+        Self { r#type: "plain_text".to_string(), text: text.to_string() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Header {
+    pub text: Text,
+}
+
+impl Header {
+    pub fn new(text: &str) -> Self {
+        Self { text: Text::plain(text) }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Section {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<Text>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Text>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessory: Option<Element>,
+}
+
+impl Section {
+    pub fn new(text: Text) -> Self {
+        Self { text: Some(text), fields: Vec::new(), accessory: None }
+    }
+
+    pub fn fields(mut self, fields: Vec<Text>) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    pub fn accessory(mut self, accessory: Element) -> Self {
+        self.accessory = Some(accessory);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Context {
+    pub elements: Vec<Element>,
+}
+
+impl Context {
+    pub fn new(elements: Vec<Element>) -> Self {
+        Self { elements }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Actions {
+    pub elements: Vec<Element>,
+}
+
+impl Actions {
+    pub fn new(elements: Vec<Element>) -> Self {
+        Self { elements }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Element {
+    Button {
+        text: Text,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        action_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        style: Option<String>,
+    },
+    Image {
+        image_url: String,
+        alt_text: String,
+    },
+    Mrkdwn {
+        text: String,
+    },
+    PlainText {
+        text: String,
+    },
+}
+
+impl Element {
+    pub fn link_button(text: &str, url: &str) -> Self {
+        Self::Button {
+            text: Text::plain(text),
+            url: Some(url.to_string()),
+            action_id: None,
+            style: None,
+        }
+    }
+
+    pub fn image(image_url: &str, alt_text: &str) -> Self {
+        Self::Image { image_url: image_url.to_string(), alt_text: alt_text.to_string() }
+    }
+
+    pub fn mrkdwn(text: &str) -> Self {
+        Self::Mrkdwn { text: text.to_string() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RichText {
+    pub elements: Vec<RichTextElement>,
+}
+
+impl RichText {
+    pub fn new(elements: Vec<RichTextElement>) -> Self {
+        Self { elements }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RichTextElement {
+    RichTextSection {
+        elements: Vec<RichTextInline>,
+    },
+    RichTextList {
+        style: String,
+        elements: Vec<RichTextElement>,
+    },
+    RichTextQuote {
+        elements: Vec<RichTextInline>,
+    },
+    RichTextPreformatted {
+        elements: Vec<RichTextInline>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RichTextInline {
+    Text {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        style: Option<RichTextStyle>,
+    },
+    Link {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<String>,
+    },
+    Emoji {
+        name: String,
+    },
+    User {
+        user_id: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+pub struct RichTextStyle {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub italic: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strike: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub code: bool,
+}
+
+impl From<Header> for Block {
+    fn from(block: Header) -> Self {
+        Self::Header(block)
+    }
+}
+
+impl From<Section> for Block {
+    fn from(block: Section) -> Self {
+        Self::Section(block)
+    }
+}
+
+impl From<Context> for Block {
+    fn from(block: Context) -> Self {
+        Self::Context(block)
+    }
+}
+
+impl From<Actions> for Block {
+    fn from(block: Actions) -> Self {
+        Self::Actions(block)
+    }
+}
+
+impl From<RichText> for Block {
+    fn from(block: RichText) -> Self {
+        Self::RichText(block)
+    }
+}
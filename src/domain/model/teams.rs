@@ -0,0 +1,173 @@
+use serde::{Serialize, Deserialize};
+
+pub const ADAPTIVE_CARD_CONTENT_TYPE: &str = "application/vnd.microsoft.card.adaptive";
+pub const ADAPTIVE_CARD_SCHEMA: &str = "http://adaptivecards.io/schemas/adaptive-card.json";
+pub const ADAPTIVE_CARD_VERSION: &str = "1.4";
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Teams {
+    pub r#type: String,
+    pub attachments: Vec<Attachment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    pub content_type: String,
+    pub content_url: Option<String>,
+    pub content: AdaptiveCard,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct AdaptiveCard {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub r#type: String,
+    pub version: String,
+    pub body: Vec<CardElement>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub msteams: Option<MsTeams>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct MsTeams {
+    pub width: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type")]
+pub enum CardElement {
+    TextBlock(TextBlock),
+    Container(Container),
+    FactSet(FactSet),
+    ActionSet(ActionSet),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TextBlock {
+    pub text: String,
+    pub wrap: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_subtle: bool,
+}
+
+impl TextBlock {
+    pub fn new(text: &str) -> Self {
+        Self { text: text.to_string(), wrap: true, size: None, weight: None, is_subtle: false }
+    }
+
+    pub fn size(mut self, size: &str) -> Self {
+        self.size = Some(size.to_string());
+        self
+    }
+
+    pub fn bolder(mut self) -> Self {
+        self.weight = Some("Bolder".to_string());
+        self
+    }
+
+    pub fn subtle(mut self) -> Self {
+        self.is_subtle = true;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Container {
+    pub items: Vec<CardElement>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub separator: bool,
+}
+
+impl Container {
+    pub fn new(items: Vec<CardElement>) -> Self {
+        Self { items, separator: false }
+    }
+
+    pub fn separator(mut self) -> Self {
+        self.separator = true;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct FactSet {
+    pub facts: Vec<Fact>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Fact {
+    pub title: String,
+    pub value: String,
+}
+
+impl Fact {
+    pub fn new(title: &str, value: &str) -> Self {
+        Self { title: title.to_string(), value: value.to_string() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ActionSet {
+    pub actions: Vec<Action>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type")]
+pub enum Action {
+    #[serde(rename = "Action.OpenUrl")]
+    OpenUrl {
+        title: String,
+        url: String,
+    },
+}
+
+impl Teams {
+    pub fn new(body: Vec<CardElement>) -> Self {
+        Self {
+            r#type: "message".to_string(),
+            attachments: vec![
+                Attachment {
+                    content_type: ADAPTIVE_CARD_CONTENT_TYPE.to_string(),
+                    content_url: None,
+                    content: AdaptiveCard {
+                        schema: ADAPTIVE_CARD_SCHEMA.to_string(),
+                        r#type: "AdaptiveCard".to_string(),
+                        version: ADAPTIVE_CARD_VERSION.to_string(),
+                        body,
+                        msteams: Some(MsTeams { width: "Full".to_string() }),
+                    },
+                }
+            ],
+        }
+    }
+}
+
+impl From<TextBlock> for CardElement {
+    fn from(element: TextBlock) -> Self {
+        Self::TextBlock(element)
+    }
+}
+
+impl From<Container> for CardElement {
+    fn from(element: Container) -> Self {
+        Self::Container(element)
+    }
+}
+
+impl From<FactSet> for CardElement {
+    fn from(element: FactSet) -> Self {
+        Self::FactSet(element)
+    }
+}
+
+impl From<ActionSet> for CardElement {
+    fn from(element: ActionSet) -> Self {
+        Self::ActionSet(element)
+    }
+}
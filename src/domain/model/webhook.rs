@@ -0,0 +1,118 @@
+//! The JSON document POSTed by the generic webhook notifier.
+//!
+//! Consumers rely on this shape, so fields are only ever added; anything
+//! else bumps `SCHEMA_VERSION`. The shape is published as
+//! `schema/digest.v<SCHEMA_VERSION>.json`.
+
+use serde::{Serialize, Deserialize};
+use chrono::NaiveDateTime;
+
+use crate::domain::model::{
+    change_request::{ChangeRequest, Forge, ReviewStatus, ReviewVerdict, TextLine},
+    digest::Digest,
+};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct DigestDocument {
+    pub version: u32,
+    pub generated_at: String,
+    pub total: usize,
+    pub repositories: Vec<RepositoryDocument>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RepositoryDocument {
+    pub forge: Forge,
+    pub name: String,
+    pub full_name: String,
+    pub url: String,
+    pub pull_requests: Vec<PullRequestDocument>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PullRequestDocument {
+    pub number: u32,
+    pub title: String,
+    pub url: String,
+    pub state: String,
+    pub draft: bool,
+    pub author: UserDocument,
+    pub created_at: String,
+    pub age_hours: i64,
+    pub age_days: i64,
+    pub classification: ReviewStatus,
+    pub reviewers: Vec<ReviewerDocument>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct UserDocument {
+    pub login: String,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ReviewerDocument {
+    pub login: String,
+    pub url: String,
+    pub state: ReviewVerdict,
+}
+
+impl DigestDocument {
+    pub fn new(digest: &Digest) -> Self {
+        let mut repositories: Vec<RepositoryDocument> = Vec::new();
+
+        for pull in &digest.pulls {
+            let document = PullRequestDocument::new(pull, digest.generated_at);
+
+            // Two forges can host the same `owner/name`; only the URL is unique.
+            match repositories.iter_mut().find(|repo| repo.forge == pull.repo.forge && repo.url == pull.repo.url) {
+                Some(repo) => repo.pull_requests.push(document),
+                None => repositories.push(RepositoryDocument {
+                    forge: pull.repo.forge,
+                    name: pull.repo.name.to_string(),
+                    full_name: pull.repo.full_name.to_string(),
+                    url: pull.repo.url.to_string(),
+                    pull_requests: vec![document],
+                }),
+            }
+        }
+
+        Self {
+            version: SCHEMA_VERSION,
+            generated_at: digest.generated_at.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            total: digest.pulls.len(),
+            repositories,
+        }
+    }
+}
+
+impl PullRequestDocument {
+    pub fn new(pull: &ChangeRequest, now: NaiveDateTime) -> Self {
+        let text_line: TextLine = pull.text_line();
+        let states = text_line.reviewer_states();
+        let age = text_line.age(now);
+
+        Self {
+            number: pull.number,
+            title: pull.title.to_string(),
+            url: pull.html_url.to_string(),
+            state: pull.state.to_string(),
+            draft: pull.draft,
+            author: UserDocument { login: pull.user.login.to_string(), url: pull.user.html_url.to_string() },
+            created_at: pull.created_at.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            age_hours: age.hours,
+            age_days: age.days,
+            classification: pull.status(),
+            reviewers: states
+                .iter()
+                .map(|(reviewer, state)| ReviewerDocument {
+                    login: reviewer.login.to_string(),
+                    url: reviewer.html_url.to_string(),
+                    state: *state,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! Plain-text helpers shared by every renderer.
+
+const ELLIPSIS: char = '…';
+
+/// Shortens `text` to at most `max_chars` characters, cutting on a char
+/// boundary and marking the cut with an ellipsis.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    if max_chars == 0 {
+        return String::new();
+    }
+
+    let mut truncated = text.chars().take(max_chars - 1).collect::<String>();
+    truncated.push(ELLIPSIS);
+
+    truncated
+}
+
+#[cfg(test)]
+mod text_test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("short", 10, "short")]
+    #[case("exactly10!", 10, "exactly10!")]
+    #[case("this is too long", 10, "this is t…")]
+    #[case("日本語のプルリクエストのタイトル", 5, "日本語の…")]
+    #[case("👨‍👩‍👧 family", 3, "👨‍…")]
+    #[case("anything", 0, "")]
+    fn test_truncate(#[case] text: &str, #[case] max_chars: usize, #[case] expected: &str) {
+        assert_eq!(expected, truncate(text, max_chars));
+    }
+}
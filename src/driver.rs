@@ -1 +1,4 @@
-pub mod batch;
\ No newline at end of file
+pub mod batch;
+pub mod cli;
+pub mod config;
+pub mod scheduler;
\ No newline at end of file
@@ -1,76 +1,518 @@
-use anyhow::Result;
-
-use crate::domain::model::github::{
-    Repository,
-    PullRequest,
-    Reviewers,
-    Review,
-    TextLine
+use std::{fs, sync::Arc};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use chrono_tz::Tz;
+use lettre::message::Mailbox;
+
+use crate::domain::model::{
+    change_request::ChangeRequest,
+    digest::Digest,
 };
 use crate::adapter::{
-    github::GithubApi,
-    slack::SlackApi,
+    azure_devops::AzureDevopsApi,
+    bitbucket::{BitbucketAuth, BitbucketCloudApi, BitbucketDataCenterApi},
+    gitea::GiteaApi,
+    github::{self, GithubApi, GITHUB_API_URI},
+    github_app::GithubApp,
+    gitlab::GitlabApi,
+    notifier::{self, Notifier},
+    error::Error,
+    secret,
+    source::{self, ChangeRequestSource},
+    chatwork::ChatworkNotifier,
+    discord::DiscordNotifier,
+    email::EmailNotifier,
+    google_chat::GoogleChatNotifier,
+    line::LineNotifier,
+    matrix::MatrixNotifier,
+    mattermost::MattermostNotifier,
+    slack::{SlackNotifier, template::Templates},
+    teams::TeamsNotifier,
+    webhook::WebhookNotifier,
+};
+use crate::driver::config::{
+    BitbucketCloudConfig,
+    AzureDevopsConfig,
+    BitbucketDataCenterConfig,
+    ChatworkNotifierConfig,
+    Config,
+    GithubConfig,
+    EmailNotifierConfig,
+    GenericWebhookNotifierConfig,
+    GiteaConfig,
+    GitlabConfig,
+    LineNotifierConfig,
+    MattermostNotifierConfig,
+    MatrixNotifierConfig,
+    NotifierConfig,
+    SlackNotifierConfig,
+    WebhookNotifierConfig,
 };
 
-const GITHUB_API_URI: &str = "https://api.github.com";
-
+/// Cheap to clone: sources and notifiers are shared, so a GitHub App's
+/// installation token outlives any one run.
+#[derive(Clone)]
 pub struct Batch {
-    github_api: GithubApi,
-    message: Vec<String>,
+    sources: Arc<Vec<Box<dyn ChangeRequestSource>>>,
+    repo_filter: Vec<String>,
+    notifiers: Arc<Vec<Box<dyn Notifier>>>,
+    escalate_after_hours: Option<i64>,
+    timezone: Tz,
 }
 
 impl Batch {
-    pub fn new() -> Self {
-        Self {
-            github_api: GithubApi::new(),
-            message: Vec::new(),
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let notifiers = if config.notifiers.is_empty() {
+            vec![Self::slack_notifier(&SlackNotifierConfig::default(), config)?]
+        } else {
+            config.notifiers
+                .iter()
+                .map(|notifier| Self::notifier(notifier, config))
+                .collect::<Result<Vec<Box<dyn Notifier>>>>()?
+        };
+
+        let mut sources = config.github
+            .iter()
+            .map(|github| Ok(Box::new(Self::github_api(github)?) as Box<dyn ChangeRequestSource>))
+            .collect::<Result<Vec<Box<dyn ChangeRequestSource>>>>()?;
+
+        if let Some(gitlab) = &config.gitlab {
+            sources.push(Box::new(Self::gitlab_api(gitlab)?));
+        }
+        if let Some(gitea) = &config.gitea {
+            sources.push(Box::new(Self::gitea_api(gitea)?));
+        }
+        if let Some(bitbucket) = &config.bitbucket_cloud {
+            sources.push(Box::new(Self::bitbucket_cloud_api(bitbucket)?));
+        }
+        if let Some(bitbucket) = &config.bitbucket_data_center {
+            sources.push(Box::new(Self::bitbucket_data_center_api(bitbucket)?));
+        }
+        if let Some(azure_devops) = &config.azure_devops {
+            sources.push(Box::new(Self::azure_devops_api(azure_devops)?));
         }
-    }
 
-    pub async fn run(&mut self) -> Result<()> {
-        let repositories = self.github_api.fetch::<Repository>(&format!("{}/user/repos?page=3", GITHUB_API_URI)).await?;
+        // github.com is only the default when no forge is configured at all.
+        if sources.is_empty() {
+            sources.push(Box::new(GithubApi::new()?));
+        }
 
-        self.create_slack_message(repositories).await?;
+        Ok(Self {
+            sources: Arc::new(sources),
+            repo_filter: Vec::new(),
+            notifiers: Arc::new(notifiers),
+            escalate_after_hours: None,
+            timezone: config.digest.timezone,
+        })
+    }
 
-        self.slack_api().await?;
+    pub fn active_sources(&self) -> &[Box<dyn ChangeRequestSource>] {
+        &self.sources
+    }
 
-        Ok(())
+    pub fn active_notifiers(&self) -> &[Box<dyn Notifier>] {
+        &self.notifiers
     }
 
-    async fn create_slack_message(&mut self, repositories: Vec<Repository>) -> Result<()> {
-        self.message.push("*Open Pull Request*".to_string());
+    pub fn filter_repositories(mut self, repos: Vec<String>) -> Self {
+        self.repo_filter = repos;
+        self
+    }
 
-        for repo in &repositories {
-            let pulls = self.github_api.fetch::<PullRequest>(&format!("{}/pulls?state=open", repo.url)).await?;
+    /// Only report pull requests overdue for review after `hours`.
+    pub fn escalation(mut self, hours: i64) -> Self {
+        self.escalate_after_hours = Some(hours);
+        self
+    }
 
-            for pull in pulls {
-                let requested_reviewers = self.github_api
-                                            .find::<Reviewers>(&format!("{}/pulls/{}/requested_reviewers", &repo.url, pull.number))
-                                            .await?;
+    /// Zone whose calendar the digest follows, e.g. for per-day threads;
+    /// `[digest] timezone` unless a job overrides it.
+    pub fn timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
 
-                if requested_reviewers.users.len() == 0 {
-                    let review = Vec::new();
-                    let text_line = TextLine::new(repo, &pull, requested_reviewers.clone(), review);
-                    self.message.push(text_line.message());
-                    continue;
-                }
+    pub async fn run(&self) -> Result<()> {
+        let digest = self.digest().await?;
 
-                let reviews = self.github_api.fetch::<Review>(&format!("{}/pulls/{}/reviews", &repo.url, pull.number)).await?;
+        if self.escalate_after_hours.is_some() && digest.pulls.is_empty() {
+            println!("Nothing to escalate");
+            return Ok(());
+        }
 
-                let text_line = TextLine::new(repo, &pull, requested_reviewers.clone(), reviews);
+        let reports = notifier::notify_all(&self.notifiers, &digest).await;
+        let total = reports.len();
 
-                self.message.push(text_line.message());
+        let mut failures = Vec::new();
+        for report in reports {
+            match report.result {
+                Ok(_) => println!("Notified {}", report.notifier),
+                Err(e) => {
+                    eprintln!("Failed to notify {}: {:?}", report.notifier, e);
+                    failures.push(e);
+                },
             }
         }
 
-        Ok(())
+        // Every failure is printed above; the first carries its cause out.
+        let count = failures.len();
+        match failures.into_iter().next() {
+            None => Ok(()),
+            Some(e) => Err(e.context(format!("{} of {} notifiers failed", count, total))),
+        }
+    }
+
+    pub async fn digest(&self) -> Result<Digest> {
+        let digest = Digest::new(self.collect().await?, Utc::now().naive_utc()).timezone(self.timezone);
+
+        Ok(match self.escalate_after_hours {
+            Some(hours) => digest.overdue(hours),
+            None => digest,
+        })
+    }
+
+    pub async fn collect(&self) -> Result<Vec<ChangeRequest>> {
+        source::collect_all(&self.sources, &self.repo_filter).await
+    }
+
+    fn github_api(github: &GithubConfig) -> Result<GithubApi> {
+        let api_uri = github.uri.as_deref().map_or(GITHUB_API_URI.to_string(), github::api_uri_for);
+
+        let api = match github.app_id {
+            Some(app_id) => GithubApi::with_app(Self::github_app(app_id, github)?.api_uri(&api_uri)),
+            None => GithubApi::with_token(&Self::secret(
+                github.token.as_deref(),
+                github.token_env.as_deref().unwrap_or("GITHUB_TOKEN"),
+            )?),
+        };
+
+        Ok(api.api_uri(&api_uri))
+    }
+
+    fn github_app(app_id: u64, github: &GithubConfig) -> Result<GithubApp> {
+        let private_key = match &github.private_key_path {
+            Some(path) => fs::read_to_string(path)
+                .with_context(|| Error::Config(format!("failed to read GitHub App private key {}", path)))?,
+            None => Self::secret(None, "GITHUB_APP_PRIVATE_KEY")?,
+        };
+
+        let app = GithubApp::new(app_id, &private_key)?;
+
+        Ok(match &github.installation {
+            Some(account) => app.installation(account),
+            None => app,
+        })
+    }
+
+    fn gitlab_api(gitlab: &GitlabConfig) -> Result<GitlabApi> {
+        let token = Self::secret(gitlab.token.as_deref(), "GITLAB_TOKEN")?;
+
+        Ok(GitlabApi::new(&gitlab.uri, &token)?
+            .projects(gitlab.projects.clone())
+            .groups(gitlab.groups.clone()))
+    }
+
+    fn gitea_api(gitea: &GiteaConfig) -> Result<GiteaApi> {
+        let token = Self::secret(gitea.token.as_deref(), "GITEA_TOKEN")?;
+
+        Ok(GiteaApi::new(&gitea.uri, &token)?.repos(gitea.repos.clone()))
+    }
+
+    fn bitbucket_cloud_api(bitbucket: &BitbucketCloudConfig) -> Result<BitbucketCloudApi> {
+        let auth = match &bitbucket.username {
+            Some(username) => BitbucketAuth::Basic(
+                username.to_string(),
+                Self::secret(bitbucket.app_password.as_deref(), "BITBUCKET_APP_PASSWORD")?,
+            ),
+            None => BitbucketAuth::Bearer(Self::secret(bitbucket.token.as_deref(), "BITBUCKET_TOKEN")?),
+        };
+
+        let mut api = BitbucketCloudApi::new(auth, bitbucket.repos.clone());
+
+        if let Some(api_uri) = &bitbucket.api_uri {
+            api = api.api_uri(api_uri);
+        }
+
+        Ok(api)
+    }
+
+    fn bitbucket_data_center_api(bitbucket: &BitbucketDataCenterConfig) -> Result<BitbucketDataCenterApi> {
+        let token = Self::secret(bitbucket.token.as_deref(), "BITBUCKET_DATA_CENTER_TOKEN")?;
+
+        Ok(BitbucketDataCenterApi::new(&bitbucket.uri, BitbucketAuth::Bearer(token), bitbucket.repos.clone()))
+    }
+
+    fn azure_devops_api(azure_devops: &AzureDevopsConfig) -> Result<AzureDevopsApi> {
+        let token = Self::secret(azure_devops.token.as_deref(), "AZURE_DEVOPS_TOKEN")?;
+
+        Ok(AzureDevopsApi::new(&azure_devops.uri, &token).projects(azure_devops.projects.clone()))
+    }
+
+    /// The configured value, else the environment variable `name` or the
+    /// file `{name}_FILE` names.
+    fn secret(value: Option<&str>, name: &str) -> Result<String> {
+        match value {
+            Some(value) => Ok(value.to_string()),
+            None => Ok(secret::var(name)?),
+        }
+    }
+
+    fn notifier(notifier: &NotifierConfig, config: &Config) -> Result<Box<dyn Notifier>> {
+        match notifier {
+            NotifierConfig::Slack(slack) => Self::slack_notifier(slack, config),
+            NotifierConfig::Teams(teams) => Self::teams_notifier(teams, config),
+            NotifierConfig::Discord(discord) => Self::discord_notifier(discord, config),
+            NotifierConfig::GoogleChat(chat) => Self::google_chat_notifier(chat, config),
+            NotifierConfig::Mattermost(mattermost) => Self::mattermost_notifier(mattermost, config),
+            NotifierConfig::Email(email) => Self::email_notifier(email, config),
+            NotifierConfig::Matrix(matrix) => Self::matrix_notifier(matrix, config),
+            NotifierConfig::Chatwork(chatwork) => Self::chatwork_notifier(chatwork, config),
+            NotifierConfig::Line(line) => Self::line_notifier(line, config),
+            NotifierConfig::Webhook(webhook) => Ok(Self::webhook_notifier(webhook, config)),
+        }
+    }
+
+    fn slack_notifier(slack: &SlackNotifierConfig, config: &Config) -> Result<Box<dyn Notifier>> {
+        let locale = slack.locale.unwrap_or(config.slack_locale());
+        let webhook_uri = Self::secret(slack.webhook_uri.as_deref(), "WEBHOOK_URI")?;
+
+        let mut notifier = SlackNotifier::new(slack.name.as_deref().unwrap_or("slack"), &webhook_uri).locale(locale);
+
+        let templates = slack.templates
+            .as_deref()
+            .or(config.templates.as_ref().map(|templates| templates.dir.as_str()));
+
+        if let Some(dir) = templates {
+            notifier = notifier.templates(Templates::from_dir(dir)?);
+        }
+
+        Ok(Box::new(notifier))
+    }
+
+    fn teams_notifier(teams: &WebhookNotifierConfig, config: &Config) -> Result<Box<dyn Notifier>> {
+        let webhook_uri = Self::secret(teams.webhook_uri.as_deref(), "TEAMS_WEBHOOK_URI")?;
+
+        Ok(Box::new(
+            TeamsNotifier::new(teams.name.as_deref().unwrap_or("teams"), &webhook_uri)
+                .locale(teams.locale.unwrap_or(config.digest.locale))
+        ))
+    }
+
+    fn discord_notifier(discord: &WebhookNotifierConfig, config: &Config) -> Result<Box<dyn Notifier>> {
+        let webhook_uri = Self::secret(discord.webhook_uri.as_deref(), "DISCORD_WEBHOOK_URI")?;
+
+        Ok(Box::new(
+            DiscordNotifier::new(discord.name.as_deref().unwrap_or("discord"), &webhook_uri)
+                .locale(discord.locale.unwrap_or(config.digest.locale))
+        ))
+    }
+
+    fn google_chat_notifier(chat: &WebhookNotifierConfig, config: &Config) -> Result<Box<dyn Notifier>> {
+        let webhook_uri = Self::secret(chat.webhook_uri.as_deref(), "GOOGLE_CHAT_WEBHOOK_URI")?;
+
+        Ok(Box::new(
+            GoogleChatNotifier::new(chat.name.as_deref().unwrap_or("google_chat"), &webhook_uri)
+                .locale(chat.locale.unwrap_or(config.digest.locale))
+        ))
+    }
+
+    fn mattermost_notifier(mattermost: &MattermostNotifierConfig, config: &Config) -> Result<Box<dyn Notifier>> {
+        let webhook_uri = Self::secret(mattermost.webhook_uri.as_deref(), "MATTERMOST_WEBHOOK_URI")?;
+
+        Ok(Box::new(
+            MattermostNotifier::new(mattermost.name.as_deref().unwrap_or("mattermost"), &webhook_uri)
+                .locale(mattermost.locale.unwrap_or(config.digest.locale))
+                .users(mattermost.users.clone())
+        ))
+    }
+
+    fn email_notifier(email: &EmailNotifierConfig, config: &Config) -> Result<Box<dyn Notifier>> {
+        let name = email.name.as_deref().unwrap_or("email");
+
+        if email.to.is_empty() {
+            return Err(Error::Config(format!("no recipients configured for {}", name)).into());
+        }
+
+        let to = email.to
+            .iter()
+            .map(|to| Self::mailbox(to))
+            .collect::<Result<Vec<Mailbox>>>()?;
+
+        let mut notifier = EmailNotifier::new(name, &email.host, Self::mailbox(&email.from)?, to)
+            .tls(email.tls)
+            .locale(email.locale.unwrap_or(config.digest.locale));
+
+        if let Some(port) = email.port {
+            notifier = notifier.port(port);
+        }
+
+        if let Some(username) = &email.username {
+            notifier = notifier.credentials(username, &Self::secret(email.password.as_deref(), "SMTP_PASSWORD")?);
+        }
+
+        Ok(Box::new(notifier))
+    }
+
+    fn mailbox(address: &str) -> Result<Mailbox> {
+        address
+            .parse::<Mailbox>()
+            .with_context(|| Error::Config(format!("invalid email address {:?}", address)))
+    }
+
+    fn matrix_notifier(matrix: &MatrixNotifierConfig, config: &Config) -> Result<Box<dyn Notifier>> {
+        let access_token = Self::secret(matrix.access_token.as_deref(), "MATRIX_ACCESS_TOKEN")?;
+
+        let mut notifier = MatrixNotifier::new(matrix.name.as_deref().unwrap_or("matrix"), &matrix.homeserver, &matrix.room_id, &access_token)
+            .locale(matrix.locale.unwrap_or(config.digest.locale));
+
+        if let Some(state_file) = &matrix.state_file {
+            notifier = notifier.state_file(state_file);
+        }
+
+        Ok(Box::new(notifier))
+    }
+
+    fn chatwork_notifier(chatwork: &ChatworkNotifierConfig, config: &Config) -> Result<Box<dyn Notifier>> {
+        let api_token = Self::secret(chatwork.api_token.as_deref(), "CHATWORK_API_TOKEN")?;
+
+        let mut notifier = ChatworkNotifier::new(chatwork.name.as_deref().unwrap_or("chatwork"), &chatwork.room_id, &api_token)
+            .locale(chatwork.locale.unwrap_or(config.digest.locale))
+            .users(chatwork.users.clone());
+
+        if let Some(api_uri) = &chatwork.api_uri {
+            notifier = notifier.api_uri(api_uri);
+        }
+
+        Ok(Box::new(notifier))
     }
 
-    async fn slack_api(&self) -> Result<()> {
-        let slack = SlackApi::construct_slack_message(&self.message);
+    fn line_notifier(line: &LineNotifierConfig, config: &Config) -> Result<Box<dyn Notifier>> {
+        let channel_access_token = Self::secret(line.channel_access_token.as_deref(), "LINE_CHANNEL_ACCESS_TOKEN")?;
 
-        SlackApi::send_message(&slack).await?;
+        let mut notifier = LineNotifier::new(line.name.as_deref().unwrap_or("line"), &line.to, &channel_access_token)
+            .locale(line.locale.unwrap_or(config.digest.locale));
 
-        Ok(()) 
+        if let Some(api_uri) = &line.api_uri {
+            notifier = notifier.api_uri(api_uri);
+        }
+
+        Ok(Box::new(notifier))
     }
-}
\ No newline at end of file
+
+    fn webhook_notifier(webhook: &GenericWebhookNotifierConfig, config: &Config) -> Box<dyn Notifier> {
+        let mut notifier = WebhookNotifier::new(webhook.name.as_deref().unwrap_or("webhook"), &webhook.uri)
+            .headers(webhook.headers.clone())
+            .locale(webhook.locale.unwrap_or(config.digest.locale));
+
+        if let Some(secret) = &webhook.secret {
+            notifier = notifier.secret(secret);
+        }
+
+        Box::new(notifier)
+    }
+}
+
+
+#[cfg(test)]
+mod batch_test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::domain::model::{change_request::Forge, locale::Locale};
+    use crate::driver::cli::{exit_code, EXIT_CONFIG};
+
+    #[test]
+    fn test_from_config_without_github() {
+        let config = Config::parse(r#"
+            [gitlab]
+            uri = "https://gitlab.example.com"
+            token = "gitlab-token"
+            projects = ["platform/gospo"]
+
+            [[notifiers]]
+            type = "webhook"
+            uri = "https://example.com/hooks/pull-requests"
+        "#).unwrap();
+
+        let batch = Batch::from_config(&config).unwrap();
+
+        assert_eq!(vec![Forge::Gitlab], batch.sources.iter().map(|source| source.forge()).collect::<Vec<Forge>>());
+    }
+
+    #[rstest]
+    #[case("", Locale::En)]
+    #[case(r#"[digest]
+              locale = "ja""#, Locale::Ja)]
+    #[case(r#"[slack]
+              locale = "ja""#, Locale::En)]
+    fn test_from_config_locale(#[case] sections: &str, #[case] expected: Locale) {
+        let config = Config::parse(&format!(r#"
+            {}
+
+            [[github]]
+            token = "github-token"
+
+            [[notifiers]]
+            type = "email"
+            host = "smtp.example.com"
+            from = "bot@example.com"
+            to = ["team@example.com"]
+        "#, sections)).unwrap();
+        let digest = Digest::new(Vec::new(), Utc::now().naive_utc());
+
+        let rendered = Batch::from_config(&config).unwrap().active_notifiers()[0].render(&digest).unwrap();
+
+        assert_eq!(digest.text(expected), rendered.text.lines().last().unwrap());
+    }
+
+    #[rstest]
+    #[case("", Tz::UTC)]
+    #[case(r#"[digest]
+              timezone = "Asia/Tokyo""#, Tz::Asia__Tokyo)]
+    fn test_from_config_timezone(#[case] digest: &str, #[case] expected: Tz) {
+        let config = Config::parse(&format!(r#"
+            {}
+
+            [[github]]
+            token = "github-token"
+
+            [[notifiers]]
+            type = "webhook"
+            uri = "https://example.com/hooks/pull-requests"
+        "#, digest)).unwrap();
+
+        assert_eq!(expected, Batch::from_config(&config).unwrap().timezone);
+    }
+
+    #[rstest]
+    #[case(r#"type = "teams""#, "TEAMS_WEBHOOK_URI is not set")]
+    #[case(r#"type = "line"
+              to = "C123""#, "LINE_CHANNEL_ACCESS_TOKEN is not set")]
+    #[case(r#"type = "email"
+              host = "smtp.example.com"
+              username = "bot"
+              from = "bot@example.com"
+              to = ["team@example.com"]"#, "SMTP_PASSWORD is not set")]
+    #[case(r#"type = "email"
+              host = "smtp.example.com"
+              from = "bot@example.com"
+              to = ["not an address"]"#, "invalid email address \"not an address\"")]
+    #[case(r#"type = "email"
+              host = "smtp.example.com"
+              from = "bot@example.com"
+              to = []"#, "no recipients configured for email")]
+    fn test_from_config_rejects_notifier(#[case] notifier: &str, #[case] message: &str) {
+        let config = Config::parse(&format!(r#"
+            [[github]]
+            token = "github-token"
+
+            [[notifiers]]
+            {}
+        "#, notifier)).unwrap();
+
+        let error = Batch::from_config(&config).err().unwrap();
+
+        assert!(error.to_string().starts_with(message), "{}", error);
+        assert_eq!(EXIT_CONFIG, exit_code(&error));
+    }
+}
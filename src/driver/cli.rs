@@ -0,0 +1,380 @@
+use std::fs;
+use anyhow::{anyhow, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::adapter::{
+    error::Error,
+    slack::{SlackError, template::Templates},
+    source::SourceError,
+};
+use crate::domain::model::{
+    digest::Digest,
+    change_request::{ChangeRequest, Forge},
+};
+use crate::driver::{
+    batch::Batch,
+    config::Config,
+    scheduler::{Job, Scheduler},
+};
+
+#[derive(Parser, Debug)]
+#[command(name = "pullrequest-slack-bot", version, about = "Notify open pull requests to Slack")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub options: Options,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct Options {
+    /// Path to the config file (defaults to $CONFIG_PATH or config.toml)
+    #[arg(long, short, global = true)]
+    pub config: Option<String>,
+
+    /// Only include these repositories (name or owner/name), repeatable
+    #[arg(long = "repo", global = true)]
+    pub repos: Vec<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    pub format: OutputFormat,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum Command {
+    /// Collect open pull requests and post them to Slack now
+    Run {
+        /// Print the Slack payload instead of posting it
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Write the dry-run payload to this file instead of stdout
+        #[arg(long, short, requires = "dry_run")]
+        output: Option<String>,
+    },
+    /// Print the rendered Slack payload without sending it
+    Preview,
+    /// Print a table of open pull requests
+    List,
+    /// Check tokens and connectivity
+    Doctor,
+    /// Inspect the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Keep running and execute the configured jobs on schedule
+    Serve,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum ConfigCommand {
+    /// Check that the config file parses and every job is valid
+    Validate,
+}
+
+/// Exit status for configuration problems; 1 covers anything unclassified
+/// and 2 is clap's usage error.
+pub const EXIT_CONFIG: u8 = 3;
+pub const EXIT_GITHUB: u8 = 4;
+pub const EXIT_SLACK: u8 = 5;
+
+#[derive(Serialize, Debug)]
+struct Check {
+    name: String,
+    ok: bool,
+    detail: String,
+}
+
+impl Check {
+    fn from(name: String, result: Result<String>) -> Self {
+        match result {
+            Ok(detail) => Self { name, ok: true, detail },
+            Err(e) => Self { name, ok: false, detail: format!("{:#}", e) },
+        }
+    }
+}
+
+impl Cli {
+    pub async fn execute(self) -> Result<()> {
+        let options = self.options;
+
+        match self.command.unwrap_or(Command::Run { dry_run: false, output: None }) {
+            Command::Run { dry_run: false, .. } => {
+                batch(&options)?.run().await
+            },
+            Command::Run { dry_run: true, output } => {
+                let batch = batch(&options)?;
+                let rendered = render_all(&batch, &batch.digest().await?)?;
+                let payload = serde_json::to_string_pretty(&payload_document(&rendered))?;
+
+                match output {
+                    Some(path) => {
+                        fs::write(&path, payload)?;
+                        eprintln!("Wrote payload to {}", path);
+                    },
+                    None => println!("{}", payload),
+                }
+
+                for (name, rendered) in &rendered {
+                    if let Some(url) = &rendered.preview_url {
+                        eprintln!("Preview {}: {}", name, url);
+                    }
+                }
+                Ok(())
+            },
+            Command::Preview => {
+                let batch = batch(&options)?;
+                let rendered = render_all(&batch, &batch.digest().await?)?;
+                println!("{}", preview(&rendered, options.format)?);
+                Ok(())
+            },
+            Command::List => {
+                let pulls = batch(&options)?.collect().await?;
+                println!("{}", list(&pulls, options.format)?);
+                Ok(())
+            },
+            Command::Doctor => {
+                doctor(&options).await
+            },
+            Command::Config { command: ConfigCommand::Validate } => {
+                validate_config(&options)
+            },
+            Command::Serve => {
+                let config = Config::load(options.config.as_deref())?;
+                Scheduler::new(&config)?.run().await
+            },
+        }
+    }
+}
+
+/// Exit status for a failed command, by the first cause it can name.
+pub fn exit_code(error: &anyhow::Error) -> u8 {
+    if matches!(error.downcast_ref::<Error>(), Some(Error::Config(_))) {
+        EXIT_CONFIG
+    } else if error.downcast_ref::<SourceError>().is_some_and(|source| source.forge == Forge::Github) {
+        EXIT_GITHUB
+    } else if error.downcast_ref::<SlackError>().is_some() {
+        EXIT_SLACK
+    } else {
+        1
+    }
+}
+
+fn batch(options: &Options) -> Result<Batch> {
+    let config = Config::load_optional(options.config.as_deref())?;
+
+    Ok(Batch::from_config(&config)?.filter_repositories(options.repos.clone()))
+}
+
+struct RenderedPayload {
+    payload: Value,
+    text: String,
+    preview_url: Option<String>,
+}
+
+fn render_all(batch: &Batch, digest: &Digest) -> Result<Vec<(String, RenderedPayload)>> {
+    batch.active_notifiers()
+        .iter()
+        .map(|notifier| {
+            let rendered = notifier.render(digest)?;
+            let preview_url = notifier.preview_url(&rendered);
+
+            Ok((
+                notifier.name().to_string(),
+                RenderedPayload { payload: serde_json::to_value(&rendered.payload)?, text: rendered.text, preview_url },
+            ))
+        })
+        .collect()
+}
+
+// A single notifier gets its exact request body; several are keyed by name.
+fn payload_document(rendered: &[(String, RenderedPayload)]) -> Value {
+    match rendered {
+        [(_, rendered)] => rendered.payload.clone(),
+        _ => Value::Object(
+            rendered
+                .iter()
+                .map(|(name, rendered)| (name.to_string(), rendered.payload.clone()))
+                .collect(),
+        ),
+    }
+}
+
+fn preview(rendered: &[(String, RenderedPayload)], format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(&payload_document(rendered))?),
+        OutputFormat::Text => match rendered {
+            [(_, rendered)] => Ok(rendered.text.to_string()),
+            _ => Ok(rendered
+                .iter()
+                .map(|(name, rendered)| format!("[{}]\n{}", name, rendered.text))
+                .collect::<Vec<String>>()
+                .join("\n\n")),
+        },
+    }
+}
+
+fn list(pulls: &[ChangeRequest], format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(pulls)?),
+        OutputFormat::Text => {
+            let rows = pulls
+                .iter()
+                .map(|pull| {
+                    vec![
+                        format!("{}#{}", pull.repo.full_name, pull.number),
+                        pull.title.to_string(),
+                        pull.user.login.to_string(),
+                        pull.created_at.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                        pull.text_line().unapproved_reviewer_logins().join(" "),
+                    ]
+                })
+                .collect::<Vec<Vec<String>>>();
+
+            Ok(table(&["PULL REQUEST", "TITLE", "AUTHOR", "CREATED", "UNAPPROVED"], &rows))
+        },
+    }
+}
+
+fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths = header.iter().map(|title| title.chars().count()).collect::<Vec<usize>>();
+
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![line(header.to_vec())];
+    lines.extend(rows.iter().map(|row| line(row.iter().map(String::as_str).collect())));
+
+    lines.join("\n")
+}
+
+async fn doctor(options: &Options) -> Result<()> {
+    let batch = batch(options)?;
+    let mut checks = Vec::new();
+
+    for source in batch.active_sources() {
+        checks.push(Check::from(source.forge().to_string(), source.check().await));
+    }
+    for notifier in batch.active_notifiers() {
+        checks.push(Check::from(notifier.name().to_string(), notifier.check().await));
+    }
+
+    match options.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&checks)?),
+        OutputFormat::Text => {
+            for check in &checks {
+                println!("[{}] {}: {}", if check.ok { "ok" } else { "ng" }, check.name, check.detail);
+            }
+        },
+    }
+
+    let failures = checks.iter().filter(|check| !check.ok).count();
+
+    if failures == 0 {
+        Ok(())
+    } else {
+        Err(anyhow!("doctor found {} problem(s)", failures))
+    }
+}
+
+fn validate_config(options: &Options) -> Result<()> {
+    let config = Config::load(options.config.as_deref())?;
+
+    for job in &config.serve.jobs {
+        Job::new(job, config.digest.timezone)?;
+    }
+
+    if let Some(templates) = &config.templates {
+        Templates::from_dir(&templates.dir)?;
+    }
+
+    println!("config OK ({} job(s))", config.serve.jobs.len());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod cli_test {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(vec!["bot"], None)]
+    #[case(vec!["bot", "run"], Some(Command::Run { dry_run: false, output: None }))]
+    #[case(vec!["bot", "run", "--dry-run"], Some(Command::Run { dry_run: true, output: None }))]
+    #[case(vec!["bot", "run", "--dry-run", "-o", "payload.json"], Some(Command::Run { dry_run: true, output: Some("payload.json".to_string()) }))]
+    #[case(vec!["bot", "preview", "--format", "json"], Some(Command::Preview))]
+    #[case(vec!["bot", "list", "--repo", "reo0306/gospo"], Some(Command::List))]
+    #[case(vec!["bot", "config", "validate", "-c", "config.toml"], Some(Command::Config { command: ConfigCommand::Validate }))]
+    #[case(vec!["bot", "serve"], Some(Command::Serve))]
+    fn test_parse_command(#[case] args: Vec<&str>, #[case] command: Option<Command>) {
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        assert_eq!(command, cli.command);
+    }
+
+    #[rstest]
+    #[case(anyhow::Error::new(Error::Config("WEBHOOK_URI is not set".to_string())), EXIT_CONFIG)]
+    #[case(anyhow!("401 Unauthorized").context(SourceError { forge: Forge::Github }), EXIT_GITHUB)]
+    #[case(anyhow!("404 Not Found").context(SourceError { forge: Forge::Gitlab }), 1)]
+    #[case(anyhow!("invalid_token").context(SlackError).context("1 of 1 notifiers failed"), EXIT_SLACK)]
+    #[case(anyhow!("unexpected"), 1)]
+    fn test_exit_code(#[case] error: anyhow::Error, #[case] code: u8) {
+        assert_eq!(code, exit_code(&error));
+    }
+
+    #[test]
+    fn test_parse_output_requires_dry_run() {
+        assert!(Cli::try_parse_from(["bot", "run", "--output", "payload.json"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let cli = Cli::try_parse_from([
+            "bot", "list", "--repo", "gospo", "--repo", "reo0306/polymer-cli", "--format", "json", "--config", "bot.toml",
+        ]).unwrap();
+
+        assert_eq!(vec!["gospo".to_string(), "reo0306/polymer-cli".to_string()], cli.options.repos);
+        assert_eq!(OutputFormat::Json, cli.options.format);
+        assert_eq!(Some("bot.toml".to_string()), cli.options.config);
+    }
+
+    #[test]
+    fn test_table() {
+        let rows = vec![
+            vec!["reo0306/gospo#1".to_string(), "Test".to_string(), "test".to_string()],
+            vec!["reo0306/polymer-cli#12".to_string(), "Add CLI".to_string(), String::new()],
+        ];
+
+        assert_eq!(
+            "PULL REQUEST            TITLE    AUTHOR\nreo0306/gospo#1         Test     test\nreo0306/polymer-cli#12  Add CLI",
+            table(&["PULL REQUEST", "TITLE", "AUTHOR"], &rows)
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use std::{collections::HashMap, env, fs, path::Path};
+use serde::Deserialize;
+use anyhow::{Context, Result};
+use chrono_tz::Tz;
+
+use crate::adapter::{email::SmtpTls, error::Error};
+use crate::domain::model::locale::Locale;
+
+const CONFIG_PATH: &str = "config.toml";
+const LOCK_FILE: &str = "pullrequest-slack-bot.lock";
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct Config {
+    #[serde(default)]
+    pub serve: ServeConfig,
+    #[serde(default)]
+    pub digest: DigestConfig,
+    #[serde(default)]
+    pub slack: SlackConfig,
+    pub templates: Option<TemplatesConfig>,
+    #[serde(default)]
+    pub github: Vec<GithubConfig>,
+    pub gitlab: Option<GitlabConfig>,
+    #[serde(alias = "forgejo")]
+    pub gitea: Option<GiteaConfig>,
+    pub bitbucket_cloud: Option<BitbucketCloudConfig>,
+    pub bitbucket_data_center: Option<BitbucketDataCenterConfig>,
+    pub azure_devops: Option<AzureDevopsConfig>,
+    #[serde(default)]
+    pub notifiers: Vec<NotifierConfig>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct DigestConfig {
+    /// Language of every notifier that does not set its own.
+    #[serde(default)]
+    pub locale: Locale,
+    /// Zone whose calendar dates the digest, e.g. Google Chat's per-day
+    /// thread and the email subject; UTC when unset.
+    #[serde(default)]
+    pub timezone: Tz,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct SlackConfig {
+    /// Overrides `[digest] locale` for Slack notifiers only.
+    pub locale: Option<Locale>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GithubConfig {
+    /// Web root (`https://github.example.com`) or REST root; github.com when
+    /// unset.
+    pub uri: Option<String>,
+    pub token: Option<String>,
+    /// Environment variable holding the token when `token` is unset;
+    /// defaults to `GITHUB_TOKEN`.
+    pub token_env: Option<String>,
+    /// Authenticate as this GitHub App instead of with a token.
+    pub app_id: Option<u64>,
+    /// PEM file of the app's private key; falls back to the PEM in
+    /// `GITHUB_APP_PRIVATE_KEY`.
+    pub private_key_path: Option<String>,
+    /// Org or user whose installation to use; needed when the app is
+    /// installed on more than one.
+    pub installation: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GitlabConfig {
+    /// Instance root, e.g. `https://gitlab.example.com`.
+    pub uri: String,
+    /// Falls back to `GITLAB_TOKEN`.
+    pub token: Option<String>,
+    /// Project paths (`group/project`) or ids.
+    #[serde(default)]
+    pub projects: Vec<String>,
+    /// Group paths or ids; includes subgroups' projects.
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GiteaConfig {
+    /// Instance root, e.g. `https://forgejo.example.com`.
+    pub uri: String,
+    /// Falls back to `GITEA_TOKEN`.
+    pub token: Option<String>,
+    /// `owner/repo` names; every repository the token can see when empty.
+    #[serde(default)]
+    pub repos: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BitbucketCloudConfig {
+    /// `workspace/slug` names.
+    pub repos: Vec<String>,
+    /// Access token; falls back to `BITBUCKET_TOKEN` unless `username` is set.
+    pub token: Option<String>,
+    /// Authenticates with an app password instead of a token.
+    pub username: Option<String>,
+    /// Falls back to `BITBUCKET_APP_PASSWORD`.
+    pub app_password: Option<String>,
+    pub api_uri: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BitbucketDataCenterConfig {
+    /// Instance root, e.g. `https://bitbucket.example.com`.
+    pub uri: String,
+    /// HTTP access token; falls back to `BITBUCKET_DATA_CENTER_TOKEN`.
+    pub token: Option<String>,
+    /// `PROJECT/slug` names.
+    pub repos: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AzureDevopsConfig {
+    /// Organization URL, e.g. `https://dev.azure.com/contoso`, or the
+    /// collection URL on Azure DevOps Server.
+    pub uri: String,
+    /// Personal access token; falls back to `AZURE_DEVOPS_TOKEN`.
+    pub token: Option<String>,
+    /// Project names; every project the token can see when empty.
+    #[serde(default)]
+    pub projects: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotifierConfig {
+    Slack(SlackNotifierConfig),
+    Teams(WebhookNotifierConfig),
+    Discord(WebhookNotifierConfig),
+    GoogleChat(WebhookNotifierConfig),
+    #[serde(alias = "rocket_chat")]
+    Mattermost(MattermostNotifierConfig),
+    Email(EmailNotifierConfig),
+    Matrix(MatrixNotifierConfig),
+    Chatwork(ChatworkNotifierConfig),
+    Line(LineNotifierConfig),
+    Webhook(GenericWebhookNotifierConfig),
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct SlackNotifierConfig {
+    pub name: Option<String>,
+    pub webhook_uri: Option<String>,
+    pub locale: Option<Locale>,
+    pub templates: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct WebhookNotifierConfig {
+    pub name: Option<String>,
+    pub webhook_uri: Option<String>,
+    pub locale: Option<Locale>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+pub struct MattermostNotifierConfig {
+    pub name: Option<String>,
+    pub webhook_uri: Option<String>,
+    pub locale: Option<Locale>,
+    /// GitHub login to chat username, for `@` mentions of pending reviewers.
+    #[serde(default)]
+    pub users: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EmailNotifierConfig {
+    pub name: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    pub username: Option<String>,
+    /// Falls back to `SMTP_PASSWORD` when `username` is set.
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    pub locale: Option<Locale>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MatrixNotifierConfig {
+    pub name: Option<String>,
+    pub homeserver: String,
+    pub room_id: String,
+    /// Falls back to `MATRIX_ACCESS_TOKEN`.
+    pub access_token: Option<String>,
+    pub locale: Option<Locale>,
+    /// Set to edit the previous digest in place instead of posting a new one.
+    pub state_file: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ChatworkNotifierConfig {
+    pub name: Option<String>,
+    pub room_id: String,
+    /// Falls back to `CHATWORK_API_TOKEN`.
+    pub api_token: Option<String>,
+    pub api_uri: Option<String>,
+    pub locale: Option<Locale>,
+    /// GitHub login to Chatwork account id, for `[To:id]` mentions of pending reviewers.
+    #[serde(default)]
+    pub users: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LineNotifierConfig {
+    pub name: Option<String>,
+    pub to: String,
+    /// Falls back to `LINE_CHANNEL_ACCESS_TOKEN`.
+    pub channel_access_token: Option<String>,
+    pub api_uri: Option<String>,
+    pub locale: Option<Locale>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GenericWebhookNotifierConfig {
+    pub name: Option<String>,
+    pub uri: String,
+    /// Signs the body as `X-Signature-256: sha256=<hmac>` when set.
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Language of the plain-text summary shown by `preview`.
+    pub locale: Option<Locale>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ServeConfig {
+    #[serde(default = "default_lock_file")]
+    pub lock_file: String,
+    #[serde(default)]
+    pub jobs: Vec<JobConfig>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TemplatesConfig {
+    pub dir: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct JobConfig {
+    pub name: String,
+    #[serde(default)]
+    pub kind: JobKind,
+    pub cron: String,
+    /// Falls back to `[digest] timezone`.
+    pub timezone: Option<String>,
+    /// For `escalation` jobs, how old a pull request must be to be reported.
+    #[serde(default = "default_escalate_after_hours")]
+    pub escalate_after_hours: i64,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    /// Every open pull request.
+    #[default]
+    Digest,
+    /// Only ready pull requests still waiting on review after
+    /// `escalate_after_hours`; nothing is sent when there are none.
+    Escalation,
+}
+
+impl Default for ServeConfig {
+    fn default() -> Self {
+        Self {
+            lock_file: default_lock_file(),
+            jobs: Vec::new(),
+        }
+    }
+}
+
+fn default_lock_file() -> String {
+    LOCK_FILE.to_string()
+}
+
+fn default_escalate_after_hours() -> i64 {
+    24
+}
+
+/// Loads `.env` into the environment when there is one; a malformed file is
+/// an error rather than silently ignored.
+pub fn load_dotenv() -> Result<()> {
+    match dotenvy::dotenv() {
+        Err(e) if !e.not_found() => Err(e).context(Error::Config("failed to load .env".to_string())),
+        _ => Ok(()),
+    }
+}
+
+impl Config {
+    /// `[slack] locale`, else `[digest] locale`.
+    pub fn slack_locale(&self) -> Locale {
+        self.slack.locale.unwrap_or(self.digest.locale)
+    }
+
+    pub fn load(path: Option<&str>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_string(),
+            None => env::var("CONFIG_PATH").unwrap_or_else(|_| CONFIG_PATH.to_string()),
+        };
+
+        Self::from_file(&path)
+    }
+
+    /// Like `load`, but falls back to the defaults when no path was given
+    /// and `config.toml` does not exist.
+    pub fn load_optional(path: Option<&str>) -> Result<Self> {
+        if path.is_none() && env::var("CONFIG_PATH").is_err() && !Path::new(CONFIG_PATH).exists() {
+            return Ok(Self::default());
+        }
+
+        Self::load(path)
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| Error::Config(format!("failed to read config file {}", path)))?;
+
+        Self::parse(&content).with_context(|| Error::Config(format!("failed to parse config file {}", path)))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str::<Self>(content)?)
+    }
+}
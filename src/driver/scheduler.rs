@@ -0,0 +1,397 @@
+use std::{
+    fs::{self, File, OpenOptions, TryLockError},
+    io::Write,
+    process,
+    str::FromStr,
+};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+
+use crate::driver::{
+    batch::Batch,
+    config::{Config, JobConfig, JobKind},
+};
+
+pub struct Job {
+    pub name: String,
+    pub kind: JobKind,
+    schedule: Schedule,
+    timezone: Tz,
+    escalate_after_hours: i64,
+}
+
+impl Job {
+    /// `timezone` applies when the job does not set its own.
+    pub fn new(config: &JobConfig, timezone: Tz) -> Result<Self> {
+        let schedule = Schedule::from_str(&config.cron)
+            .map_err(|e| anyhow!("job {}: invalid cron expression {:?}: {}", config.name, config.cron, e))?;
+
+        let timezone = match &config.timezone {
+            Some(name) => Tz::from_str(name).map_err(|e| anyhow!("job {}: invalid timezone {:?}: {}", config.name, name, e))?,
+            None => timezone,
+        };
+
+        Ok(Self {
+            name: config.name.to_string(),
+            kind: config.kind,
+            schedule,
+            timezone,
+            escalate_after_hours: config.escalate_after_hours,
+        })
+    }
+
+    pub fn next_after(&self, now: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.schedule
+            .after(&now.with_timezone(&self.timezone))
+            .next()
+            .map(|next| next.with_timezone(&Utc))
+    }
+}
+
+pub struct Scheduler {
+    jobs: Vec<Job>,
+    config: Config,
+    // Built once so cached credentials carry over from one job to the next.
+    batch: Batch,
+}
+
+impl Scheduler {
+    pub fn new(config: &Config) -> Result<Self> {
+        if config.serve.jobs.is_empty() {
+            return Err(anyhow!("no jobs configured for serve mode"));
+        }
+
+        let jobs = config.serve.jobs
+            .iter()
+            .map(|job| Job::new(job, config.digest.timezone))
+            .collect::<Result<Vec<Job>>>()?;
+
+        Ok(Self { jobs, config: config.clone(), batch: Batch::from_config(config)? })
+    }
+
+    pub fn next_run(&self, now: &DateTime<Utc>) -> Option<(DateTime<Utc>, Vec<&Job>)> {
+        let upcoming = self.jobs
+            .iter()
+            .filter_map(|job| job.next_after(now).map(|next| (next, job)))
+            .collect::<Vec<(DateTime<Utc>, &Job)>>();
+
+        let earliest = upcoming.iter().map(|(next, _)| *next).min()?;
+
+        let due = upcoming
+            .into_iter()
+            .filter(|(next, _)| *next == earliest)
+            .map(|(_, job)| job)
+            .collect();
+
+        Some((earliest, due))
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        let _lock = LockFile::acquire(&self.config.serve.lock_file)?;
+
+        let shutdown = shutdown_signal();
+        tokio::pin!(shutdown);
+
+        loop {
+            let now = Utc::now();
+
+            let Some((next, due)) = self.next_run(&now) else {
+                println!("No upcoming job, stopping scheduler");
+                break;
+            };
+
+            let wait = (next - now).to_std().unwrap_or_default();
+
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {
+                    for job in due {
+                        println!("Running job {} at {}", job.name, next);
+
+                        if let Err(e) = self.run_job(job).await {
+                            eprintln!("Job {} failed: {:?}", job.name, e);
+                        }
+                    }
+                },
+                _ = &mut shutdown => {
+                    println!("Shutdown signal received, stopping scheduler");
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn run_job(&self, job: &Job) -> Result<()> {
+        let batch = self.batch.clone().timezone(job.timezone);
+
+        match job.kind {
+            JobKind::Digest => batch.run().await,
+            JobKind::Escalation => batch.escalation(job.escalate_after_hours).run().await,
+        }
+    }
+}
+
+/// An advisory lock held for as long as the value lives. The kernel drops it
+/// when the process exits, however it exits, so a file left behind by a
+/// crash never blocks the next start.
+pub struct LockFile {
+    _file: File,
+}
+
+impl LockFile {
+    pub fn acquire(path: &str) -> Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .with_context(|| format!("failed to open lock file {}", path))?;
+
+        match file.try_lock() {
+            Ok(()) => {},
+            Err(TryLockError::WouldBlock) => {
+                let pid = fs::read_to_string(path).unwrap_or_default();
+                return Err(anyhow!("another instance is already running (lock file {}, pid {})", path, pid.trim()));
+            },
+            Err(TryLockError::Error(e)) => {
+                return Err(e).with_context(|| format!("failed to lock {}", path));
+            },
+        }
+
+        file.set_len(0)?;
+        write!(file, "{}", process::id())?;
+
+        Ok(Self { _file: file })
+    }
+}
+
+#[cfg(unix)]
+async fn shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate()).expect("failed to install SIGTERM handler");
+
+    tokio::select! {
+        _ = terminate.recv() => {},
+        _ = tokio::signal::ctrl_c() => {},
+    }
+}
+
+#[cfg(not(unix))]
+async fn shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(test)]
+mod scheduler_test {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::adapter::github_app::github_app_test::PRIVATE_KEY;
+    use crate::driver::config::ServeConfig;
+
+    fn job_config(name: &str, cron: &str, timezone: &str) -> JobConfig {
+        JobConfig {
+            name: name.to_string(),
+            kind: JobKind::Digest,
+            cron: cron.to_string(),
+            timezone: Some(timezone.to_string()),
+            escalate_after_hours: 24,
+        }
+    }
+
+    #[rstest]
+    #[case("0 30 9 * * Mon-Fri", "Asia/Tokyo", "2024-07-19T01:00:00Z", "2024-07-22T00:30:00Z")]
+    #[case("0 30 9 * * Mon-Fri", "Asia/Tokyo", "2024-07-18T00:00:00Z", "2024-07-18T00:30:00Z")]
+    #[case("0 0 * * * *", "UTC", "2024-07-18T10:15:00Z", "2024-07-18T11:00:00Z")]
+    fn test_job_next_after(
+        #[case] cron: &str,
+        #[case] timezone: &str,
+        #[case] now: &str,
+        #[case] expected: &str,
+    ) {
+        let job = Job::new(&job_config("digest", cron, timezone), Tz::UTC).unwrap();
+
+        let now = DateTime::parse_from_rfc3339(now).unwrap().with_timezone(&Utc);
+        let expected = DateTime::parse_from_rfc3339(expected).unwrap().with_timezone(&Utc);
+
+        assert_eq!(Some(expected), job.next_after(&now));
+    }
+
+    #[rstest]
+    #[case(job_config("digest", "every morning", "UTC"))]
+    #[case(job_config("digest", "0 30 9 * * Mon-Fri", "Mars/Olympus"))]
+    fn test_job_invalid(#[case] config: JobConfig) {
+        assert!(Job::new(&config, Tz::UTC).is_err());
+    }
+
+    #[test]
+    fn test_scheduler_next_run() {
+        let config = Config {
+            serve: ServeConfig {
+                lock_file: String::new(),
+                jobs: vec![
+                    job_config("digest", "0 30 9 * * Mon-Fri", "Asia/Tokyo"),
+                    job_config("escalation", "0 30 * * * *", "UTC"),
+                    job_config("weekly", "0 0 10 * * Mon", "UTC"),
+                ],
+            },
+            ..Config::parse(r#"
+                [[github]]
+                token = "github-token"
+
+                [[notifiers]]
+                type = "webhook"
+                uri = "https://example.com/hooks/pull-requests"
+            "#).unwrap()
+        };
+        let scheduler = Scheduler::new(&config).unwrap();
+
+        let now = DateTime::parse_from_rfc3339("2024-07-18T00:10:00Z").unwrap().with_timezone(&Utc);
+        let (next, due) = scheduler.next_run(&now).unwrap();
+
+        assert_eq!("2024-07-18T00:30:00+00:00", next.to_rfc3339());
+        assert_eq!(vec!["digest", "escalation"], due.iter().map(|job| job.name.as_str()).collect::<Vec<&str>>());
+    }
+
+    #[test]
+    fn test_job_timezone_falls_back_to_digest() {
+        let config = Config::parse(r#"
+            [digest]
+            timezone = "Asia/Tokyo"
+
+            [[github]]
+            token = "github-token"
+
+            [[notifiers]]
+            type = "webhook"
+            uri = "https://example.com/hooks/pull-requests"
+
+            [[serve.jobs]]
+            name = "digest"
+            cron = "0 30 9 * * Mon-Fri"
+
+            [[serve.jobs]]
+            name = "escalation"
+            cron = "0 30 9 * * Mon-Fri"
+            timezone = "UTC"
+        "#).unwrap();
+
+        let scheduler = Scheduler::new(&config).unwrap();
+
+        assert_eq!(
+            vec![Tz::Asia__Tokyo, Tz::UTC],
+            scheduler.jobs.iter().map(|job| job.timezone).collect::<Vec<Tz>>()
+        );
+    }
+
+    #[test]
+    fn test_scheduler_without_jobs() {
+        assert!(Scheduler::new(&Config::default()).is_err());
+    }
+
+    #[test]
+    fn test_lock_file() {
+        let path = env_temp_path("pullrequest-slack-bot-test.lock");
+
+        let lock = LockFile::acquire(&path).unwrap();
+        assert!(LockFile::acquire(&path).is_err());
+
+        drop(lock);
+        assert!(LockFile::acquire(&path).is_ok());
+    }
+
+    #[test]
+    fn test_lock_file_left_behind() {
+        let path = env_temp_path("pullrequest-slack-bot-stale-test.lock");
+        fs::write(&path, "999999999").unwrap();
+
+        let _lock = LockFile::acquire(&path).unwrap();
+
+        assert_eq!(process::id().to_string(), fs::read_to_string(&path).unwrap());
+    }
+
+    #[test]
+    fn test_job_kind() {
+        let config = Config::parse(r#"
+            [[serve.jobs]]
+            name = "digest"
+            cron = "0 30 9 * * Mon-Fri"
+
+            [[serve.jobs]]
+            name = "escalation"
+            kind = "escalation"
+            cron = "0 0 * * * *"
+            escalate_after_hours = 48
+        "#).unwrap();
+
+        let jobs = config.serve.jobs.iter().map(|job| Job::new(job, Tz::UTC).unwrap()).collect::<Vec<Job>>();
+
+        assert_eq!(
+            vec![(JobKind::Digest, 24), (JobKind::Escalation, 48)],
+            jobs.iter().map(|job| (job.kind, job.escalate_after_hours)).collect::<Vec<(JobKind, i64)>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_reuses_installation_token() {
+        let mut server = mockito::Server::new_async().await;
+        let private_key_path = env_temp_path("pullrequest-slack-bot-test.pem");
+        fs::write(&private_key_path, PRIVATE_KEY).unwrap();
+
+        let config = Config::parse(&format!(r#"
+            [[github]]
+            uri = "{uri}/api/v3"
+            app_id = 12345
+            private_key_path = '{private_key_path}'
+
+            [[notifiers]]
+            type = "webhook"
+            uri = "{uri}/hooks/pull-requests"
+
+            [[serve.jobs]]
+            name = "digest"
+            cron = "0 30 9 * * Mon-Fri"
+        "#, uri = server.url(), private_key_path = private_key_path)).unwrap();
+
+        let installations = server
+            .mock("GET", "/api/v3/app/installations?per_page=100")
+            .with_body(serde_json::json!([{ "id": 1, "account": { "login": "acme", "html_url": "https://github.com/acme" } }]).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let access_token = server
+            .mock("POST", "/api/v3/app/installations/1/access_tokens")
+            .with_status(201)
+            .with_body(serde_json::json!({ "token": "ghs_acme", "expires_at": (Utc::now() + chrono::Duration::hours(1)).to_rfc3339() }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let repositories = server
+            .mock("GET", "/api/v3/installation/repositories?per_page=100")
+            .match_header("authorization", "Bearer ghs_acme")
+            .with_body(r#"{ "total_count": 0, "repositories": [] }"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let hook = server.mock("POST", "/hooks/pull-requests").expect(2).create_async().await;
+
+        let scheduler = Scheduler::new(&config).unwrap();
+
+        scheduler.run_job(&scheduler.jobs[0]).await.unwrap();
+        scheduler.run_job(&scheduler.jobs[0]).await.unwrap();
+
+        installations.assert_async().await;
+        access_token.assert_async().await;
+        repositories.assert_async().await;
+        hook.assert_async().await;
+    }
+
+    fn env_temp_path(name: &str) -> String {
+        std::env::temp_dir().join(name).to_string_lossy().to_string()
+    }
+}
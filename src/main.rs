@@ -1,14 +1,22 @@
-use anyhow::Result;
+use std::process::ExitCode;
+use clap::Parser;
 
-use pullrequest_slack_bot::driver::batch::Batch;
+use pullrequest_slack_bot::driver::{cli::{self, Cli}, config};
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    dotenvy::dotenv()?;
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
 
-    let mut batch = Batch::new();
+    let result = match config::load_dotenv() {
+        Ok(()) => cli.execute().await,
+        Err(e) => Err(e),
+    };
 
-    batch.run().await.expect("slack bot error");
-
-    Ok(())
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from(cli::exit_code(&e))
+        },
+    }
 }